@@ -22,7 +22,7 @@ fn main() {
     let b = [[std::f32::consts::PI, 1.], [1., std::f32::consts::PI]];
     let result = executor::block_on(addition::<burn::backend::NdArray>(a, b));
     if cfg!(feature = "cocos") {
-        match lib::save_results_to_file(result.to_string(), "results/results.txt".to_string()) {
+        match lib::save_results(result.to_string()) {
             Ok(_) => (),
             Err(e) => {
                 eprintln!("{}", e);
@@ -21,10 +21,7 @@ pub fn run() {
     match addition::<burn::backend::NdArray>(a, b) {
         Ok(result) => {
             if cfg!(feature = "cocos") {
-                match lib::save_results_to_file(
-                    result.to_string(),
-                    "results/results.txt".to_string(),
-                ) {
+                match lib::save_results(result.to_string()) {
                     Ok(_) => (),
                     Err(e) => {
                         eprintln!("{}", e);
@@ -58,14 +55,57 @@ pub fn run_wgpu() {
 #[cfg(feature = "read")]
 pub fn read_binary_results() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: read-results <results-file>");
-        std::process::exit(1);
-    }
-    let results = std::fs::read(&args[1]).expect("Failed to read file");
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let results = match args[1..] {
+        ["keygen", dir] => match lib::encryption::write_keypair(dir) {
+            Ok((secret, public)) => {
+                println!("Secret key: {}", secret.display());
+                println!("Public key: {}", public.display());
+                return;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        ["decrypt", key_file, results_file] => {
+            match verify_results(results_file).and_then(|sealed| {
+                let secret = std::fs::read_to_string(key_file)?;
+                let secret = lib::encryption::secret_key_from_hex(&secret)?;
+                lib::encryption::open(&secret, &sealed)
+            }) {
+                Ok(results) => results,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        [results_file] => match verify_results(results_file) {
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Usage: read-results <results-file>");
+            eprintln!("       read-results decrypt <secret-key-file> <sealed-results-file>");
+            eprintln!("       read-results keygen <output-dir>");
+            std::process::exit(1);
+        }
+    };
     println!("{:?}", String::from_utf8_lossy(&results));
 }
 
+/// Checks the signed manifest next to `path` before the results are trusted. The key
+/// comes from `RESULTS_VERIFYING_KEY`, never from the `signing.pub` next to the manifest.
+#[cfg(feature = "read")]
+fn verify_results(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let key = lib::signing::verifying_key()?;
+    lib::signing::read_verified(path, &key)
+}
+
 fn main() {
     #[cfg(any(feature = "ndarray", feature = "cocos"))]
     run();
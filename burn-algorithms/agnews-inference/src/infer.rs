@@ -0,0 +1,121 @@
+use crate::model::{build_and_load_model, model_source};
+use lib::{AlgorithmError, Classification};
+use std::path::PathBuf;
+
+/// Classifies the news `text`, its title and description joined with a space like in
+/// training, with the model found by [`model_source`] from `model_path`. Returns the
+/// probability of every class and the `top_k` most likely ones.
+pub async fn inference(
+    text: &str,
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Classification, AlgorithmError> {
+    let mut classifications = batch_inference(&[text], model_path, top_k).await?;
+
+    Ok(classifications.remove(0))
+}
+
+/// Like [`inference`] for every text of a batch, in order, with a single forward pass.
+pub async fn batch_inference(
+    texts: &[&str],
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Vec<Classification>, AlgorithmError> {
+    if texts.is_empty() {
+        return Err(AlgorithmError::InvalidDataset(
+            "The batch has no items".to_string(),
+        ));
+    }
+
+    let source = model_source(model_path)?;
+    let classifier = build_and_load_model(&source)?;
+
+    classifier.classify(texts, top_k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::NDBackend;
+    use burn::{
+        module::Module,
+        nn::transformer::TransformerEncoderConfig,
+        record::{HalfPrecisionSettings, NamedMpkBytesRecorder, Recorder},
+    };
+    use futures::executor;
+    use lib::{
+        config::CONFIG_FILE,
+        text_model::{TextClassificationModel, TextModelConfig, TEXT_MODEL_FILE},
+        tokenizer::{Tokenizer, TOKENIZER_FILE},
+        WordPieceTokenizer,
+    };
+    use std::fs;
+
+    static TOKENIZER_JSON: &[u8] = include_bytes!("../../lib/fixtures/bert-cased/tokenizer.json");
+
+    /// Saves a small untrained model like the training does: `model.mpk` with
+    /// `CompactRecorder`, next to its `config.json` and `tokenizer.json`.
+    fn save_model(dir: &std::path::Path) {
+        let config = serde_json::json!({
+            "dataset": {
+                "text_columns": ["title", "description"],
+                "label_column": "label",
+                "labels": ["1", "2", "3", "4"],
+                "class_names": ["World", "Sports", "Business", "Technology"],
+                "train_file": "train.csv",
+            },
+            "transformer": TransformerEncoderConfig::new(16, 32, 2, 1),
+            "max_seq_length": 32,
+            "batch_size": 32,
+        });
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE), config.to_string()).unwrap();
+        fs::write(dir.join(TOKENIZER_FILE), TOKENIZER_JSON).unwrap();
+
+        let config = TextModelConfig::from_slice(config.to_string().as_bytes()).unwrap();
+        let vocab_size = WordPieceTokenizer::from_tokenizer_json(TOKENIZER_JSON)
+            .unwrap()
+            .vocab_size();
+        let model: TextClassificationModel<NDBackend> =
+            TextClassificationModel::new(&config, 4, vocab_size, &Default::default());
+        let bytes = NamedMpkBytesRecorder::<HalfPrecisionSettings>::default()
+            .record(model.into_record(), ())
+            .unwrap();
+        fs::write(dir.join(TEXT_MODEL_FILE), bytes).unwrap();
+    }
+
+    #[test]
+    fn text_is_classified_with_the_saved_model() {
+        let dir = std::env::temp_dir().join(format!("agnews-inference-{}", std::process::id()));
+        save_model(&dir);
+
+        let texts = [
+            "Stocks rally as markets recover.",
+            "The team won the final!",
+        ];
+        let classifications =
+            executor::block_on(batch_inference(&texts, Some(dir.clone()), 2)).unwrap();
+
+        assert_eq!(classifications.len(), 2);
+        for classification in &classifications {
+            let total = classification
+                .probabilities
+                .iter()
+                .map(|class| class.probability)
+                .sum::<f32>();
+            assert!((total - 1.0).abs() < 1e-4);
+            assert_eq!(classification.top_k.len(), 2);
+            assert!(["World", "Sports", "Business", "Technology"]
+                .contains(&classification.class.as_str()));
+        }
+        let single = executor::block_on(inference(texts[1], Some(dir.clone()), 2)).unwrap();
+        assert_eq!(single.label, classifications[1].label);
+        assert!((single.probability - classifications[1].probability).abs() < 1e-4);
+
+        fs::remove_file(dir.join(TOKENIZER_FILE)).unwrap();
+        let err = executor::block_on(inference(texts[0], Some(dir.clone()), 2)).unwrap_err();
+        assert_eq!(err.kind(), "missing_file");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
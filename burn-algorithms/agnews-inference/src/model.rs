@@ -0,0 +1,32 @@
+use burn::record::{HalfPrecisionSettings, NamedMpkBytesRecorder};
+use lib::{
+    results::DATASETS_DIR,
+    text_model::{TextClassifier, TEXT_MODEL_FILE},
+    AlgorithmError, ModelSource,
+};
+use std::path::PathBuf;
+
+pub type NDBackend = burn::backend::ndarray::NdArray<f32>;
+
+static STATE_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/model.mpk"));
+static CONFIG_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/config.json"));
+static TOKENIZER_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/tokenizer.json"));
+
+/// Finds the model at `path`, in the datasets directory, or embedded in the binary.
+pub fn model_source(path: Option<PathBuf>) -> Result<ModelSource, AlgorithmError> {
+    ModelSource::resolve_file_in(path, DATASETS_DIR, TEXT_MODEL_FILE, STATE_ENCODED)
+}
+
+/// Builds the model described by the training config saved next to it, loads it, and
+/// reads the tokenizer saved with it.
+pub fn build_and_load_model(
+    source: &ModelSource,
+) -> Result<TextClassifier<NDBackend>, AlgorithmError> {
+    TextClassifier::load(
+        source,
+        CONFIG_ENCODED,
+        TOKENIZER_ENCODED,
+        &NamedMpkBytesRecorder::<HalfPrecisionSettings>::default(),
+        &Default::default(),
+    )
+}
@@ -1,3 +1 @@
-pub mod data;
-pub mod model;
 pub mod training;
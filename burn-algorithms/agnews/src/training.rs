@@ -1,116 +1,59 @@
-use crate::{
-    data::{BertCasedTokenizer, ClassificationBatcher, ClassificationDataset, Tokenizer},
-    model::ModelConfig,
-};
-use burn::{
-    data::{dataloader::DataLoaderBuilder, dataset::transform::SamplerDataset},
-    lr_scheduler::noam::NoamLrSchedulerConfig,
-    nn::transformer::TransformerEncoderConfig,
-    optim::AdamConfig,
-    prelude::*,
-    record::{CompactRecorder, Recorder},
-    tensor::backend::AutodiffBackend,
-    train::{
-        metric::{AccuracyMetric, LearningRateMetric, LossMetric},
-        LearnerBuilder,
-    },
-};
-use std::sync::Arc;
-
-#[derive(Config)]
-pub struct TrainingConfig {
-    pub transformer: TransformerEncoderConfig,
-    pub optimizer: AdamConfig,
-
-    #[config(default = 512)]
-    pub max_seq_length: usize,
-    #[config(default = 32)]
-    pub batch_size: usize,
-    #[config(default = 5)]
-    pub num_epochs: usize,
-    #[config(default = 1)]
-    pub num_workers: usize,
-    #[config(default = 1e-2)]
-    pub learning_rate: f64,
+use burn::tensor::backend::AutodiffBackend;
+use std::path::{Path, PathBuf};
+use text_classification::{dataset::CsvDatasetConfig, training::ExperimentConfig};
+
+#[cfg(feature = "cocos")]
+static ARTIFACT_DIR: &str = "results";
+
+#[cfg(not(feature = "cocos"))]
+static ARTIFACT_DIR: &str = "artifacts/agnews/";
+
+/// The AG News CSV files, with the `label,title,description` header added as described
+/// in the README. Labels go from 1 to 4.
+pub fn dataset_config() -> CsvDatasetConfig {
+    CsvDatasetConfig::new(
+        vec!["title".to_string(), "description".to_string()],
+        "label".to_string(),
+        vec!["1", "2", "3", "4"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        "train.csv".to_string(),
+    )
+    .with_class_names(
+        vec!["World", "Sports", "Business", "Technology"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    )
+    .with_test_file(Some("test.csv".to_string()))
 }
 
-pub fn train<B: AutodiffBackend, D: ClassificationDataset + 'static>(
-    device: B::Device,
-    dataset_train: D,
-    dataset_test: D,
-    config: TrainingConfig,
-    artifact_dir: &str,
-) {
-    let tokenizer = Arc::new(BertCasedTokenizer::default());
+/// Under cocos the files come from the uploaded `train` dataset, usually the
+/// `ag_news_csv` archive, or from a separate `test` upload.
+fn data_dir() -> Option<PathBuf> {
+    if cfg!(feature = "cocos") {
+        return None;
+    }
+
+    let example_dir = Path::new(file!())
+        .parent()
+        .expect("Failed to get parent")
+        .parent()
+        .expect("Failed to get parent");
+    let agnews_dir = example_dir.join("data/ag_news_csv/");
+    if !agnews_dir.join("train.csv").exists() {
+        eprintln!("Download the AG News dataset from https://s3.amazonaws.com/fast-ai-nlp/ag_news_csv.tgz and place it in the data directory");
+    }
+
+    Some(agnews_dir)
+}
 
-    let batcher_train =
-        ClassificationBatcher::<B>::new(tokenizer.clone(), device.clone(), config.max_seq_length);
-    let batcher_test = ClassificationBatcher::<B::InnerBackend>::new(
-        tokenizer.clone(),
-        device.clone(),
-        config.max_seq_length,
+pub fn run<B: AutodiffBackend>(device: B::Device) {
+    text_classification::training::run_csv::<B>(
+        device,
+        ExperimentConfig::with_defaults(dataset_config()),
+        data_dir().as_deref(),
+        ARTIFACT_DIR,
     );
-
-    let model = ModelConfig::new(
-        config.transformer.clone(),
-        D::num_classes(),
-        tokenizer.vocab_size(),
-        config.max_seq_length,
-    )
-    .init(&device);
-
-    let dataloader_train = DataLoaderBuilder::new(batcher_train)
-        .batch_size(config.batch_size)
-        .num_workers(config.num_workers)
-        .build(SamplerDataset::new(dataset_train, 50_000));
-    let dataloader_test = DataLoaderBuilder::new(batcher_test)
-        .batch_size(config.batch_size)
-        .num_workers(config.num_workers)
-        .build(SamplerDataset::new(dataset_test, 5_000));
-
-    let optim = config.optimizer.init();
-
-    let lr_scheduler = NoamLrSchedulerConfig::new(config.learning_rate)
-        .with_warmup_steps(1000)
-        .with_model_size(config.transformer.d_model)
-        .init()
-        .unwrap();
-
-    let learner = if cfg!(feature = "cocos") {
-        LearnerBuilder::new(artifact_dir)
-            .metric_train_numeric(AccuracyMetric::new())
-            .metric_valid_numeric(AccuracyMetric::new())
-            .metric_train_numeric(LossMetric::new())
-            .metric_valid_numeric(LossMetric::new())
-            .metric_train_numeric(LearningRateMetric::new())
-            .with_file_checkpointer(CompactRecorder::new())
-            .devices(vec![device.clone()])
-            .num_epochs(config.num_epochs)
-            .renderer(lib::EmptyMetricsRenderer)
-            .build(model, optim, lr_scheduler)
-    } else {
-        LearnerBuilder::new(artifact_dir)
-            .metric_train_numeric(AccuracyMetric::new())
-            .metric_valid_numeric(AccuracyMetric::new())
-            .metric_train_numeric(LossMetric::new())
-            .metric_valid_numeric(LossMetric::new())
-            .metric_train_numeric(LearningRateMetric::new())
-            .with_file_checkpointer(CompactRecorder::new())
-            .devices(vec![device.clone()])
-            .num_epochs(config.num_epochs)
-            .summary()
-            .build(model, optim, lr_scheduler)
-    };
-
-    let model_trained = learner.fit(dataloader_train, dataloader_test);
-
-    config
-        .save(format!("{artifact_dir}/config.json"))
-        .expect("Config should be saved successfully");
-    CompactRecorder::new()
-        .record(
-            model_trained.into_record(),
-            format!("{artifact_dir}/model").into(),
-        )
-        .expect("Model should be saved successfully");
 }
@@ -0,0 +1,17 @@
+use std::{env, fs, path::Path};
+
+/// The model the CIFAR-10 training saves, embedded when it exists. A binary built
+/// before the model was trained loads it from `--model` or the datasets directory.
+const ARTIFACT: &str = "model.mpk";
+
+fn main() {
+    let out = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../artifacts/cifar10")
+        .join(ARTIFACT);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let data = fs::read(&path).unwrap_or_default();
+    fs::write(Path::new(&out).join(ARTIFACT), data)
+        .unwrap_or_else(|e| panic!("Failed to embed {}: {}", ARTIFACT, e));
+}
@@ -0,0 +1,85 @@
+// The command line of the cifar10 inference, run by `cifar10-inference` and by
+// `burn-algorithms infer cifar10`
+
+use crate::infer::{directory_inference, inference, is_image};
+use futures::executor;
+use lib::{
+    classification::DEFAULT_TOP_K,
+    results::{DATASETS_DIR, RESULTS_DIR},
+    AlgorithmError,
+};
+use std::path::{Path, PathBuf};
+
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} [--model <path>] [--top-k <k>] <image, or directory or archive of images>",
+        program
+    );
+    std::process::exit(1);
+}
+
+/// Parses `args`, the arguments after `program`, classifies the images and prints the
+/// result, or saves it under cocos. Exits with the code of the error when the inference
+/// fails.
+pub fn run(program: &str, args: &[String]) {
+    let mut model_path = None;
+    let mut top_k = DEFAULT_TOP_K;
+    let mut input = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--model" => match rest.next() {
+                Some(path) => model_path = Some(PathBuf::from(path)),
+                None => usage(program),
+            },
+            "--top-k" => match rest.next().map(|k| k.parse()) {
+                Some(Ok(k)) => top_k = k,
+                Some(Err(e)) => {
+                    eprintln!("Invalid --top-k: {}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => usage(program),
+        }
+    }
+    // In cocos, the images are uploaded to the datasets directory
+    let input = match input {
+        Some(input) => Path::new(input),
+        None if cfg!(feature = "cocos") => Path::new(DATASETS_DIR),
+        None => usage(program),
+    };
+
+    let result = match !is_image(input) {
+        true => executor::block_on(directory_inference(input, model_path, top_k)).map(
+            |classifications| {
+                serde_json::to_string(&classifications)
+                    .expect("Failed to serialize classifications")
+            },
+        ),
+        false => executor::block_on(inference(input, model_path, top_k)).map(|classification| {
+            serde_json::to_string(&classification).expect("Failed to serialize classification")
+        }),
+    };
+    output(result);
+}
+
+fn output(result: Result<String, AlgorithmError>) {
+    match result {
+        Ok(result) => {
+            if cfg!(feature = "cocos") {
+                match lib::save_results(result) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{:}", result);
+            }
+        }
+        Err(e) => lib::exit_with_error(RESULTS_DIR, e),
+    };
+}
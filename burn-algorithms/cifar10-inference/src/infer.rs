@@ -0,0 +1,344 @@
+use crate::model::{build_and_load_model, model_source, NDBackend, CLASSES};
+use burn::tensor::{activation::softmax, Shape, Tensor, TensorData};
+use image::{imageops::FilterType, ImageFormat};
+use lib::{
+    preprocessing::{normalize_cifar10, CIFAR10_IMAGE_SIZE},
+    AlgorithmError, Classification,
+};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Number of leading bytes read to recognize an image file.
+const IMAGE_HEADER_LEN: u64 = 16;
+
+/// The classification of one image of a directory, with the name of its file.
+#[derive(Debug, Serialize)]
+pub struct ImageClassification {
+    pub image: String,
+    #[serde(flatten)]
+    pub classification: Classification,
+}
+
+/// Decodes a PNG or JPEG image and resizes it to the 32x32 RGB images of CIFAR-10. The
+/// pixels are laid out like the dataset items, row by row with the channels of each
+/// pixel next to each other.
+pub fn image_pixels(bytes: &[u8]) -> Result<Vec<u8>, image::ImageError> {
+    let image = image::load_from_memory(bytes)?;
+    let size = CIFAR10_IMAGE_SIZE as u32;
+    let image = match image.width() == size && image.height() == size {
+        true => image,
+        false => image.resize_exact(size, size, FilterType::Triangle),
+    };
+
+    Ok(image.into_rgb8().into_raw())
+}
+
+/// The image files to classify at `path`: the image itself, or the images of a directory
+/// or of a tar.gz or zip archive, sorted by name. Files are recognized by their content,
+/// since uploaded datasets lose their names, so files that are not images are skipped.
+pub fn image_files(path: &Path) -> Result<Vec<PathBuf>, AlgorithmError> {
+    if !path.exists() {
+        return Err(AlgorithmError::MissingFile(path.to_path_buf()));
+    }
+
+    let mut files = Vec::new();
+    collect_images(path, &mut files)?;
+
+    match files.is_empty() {
+        true => Err(AlgorithmError::InvalidDataset(format!(
+            "No PNG or JPEG images in {}",
+            path.display()
+        ))),
+        false => Ok(files),
+    }
+}
+
+fn collect_images(path: &Path, images: &mut Vec<PathBuf>) -> Result<(), AlgorithmError> {
+    if is_image(path) {
+        images.push(path.to_path_buf());
+        return Ok(());
+    }
+    if path.is_file() {
+        return match unpack_archive(path)? {
+            Some(dir) => collect_images(&dir, images),
+            None => Ok(()),
+        };
+    }
+
+    let mut entries = fs::read_dir(path)
+        .map_err(|source| AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    entries
+        .iter()
+        .try_for_each(|entry| collect_images(entry, images))
+}
+
+/// Whether the file at `path` starts like a PNG or JPEG image.
+pub fn is_image(path: &Path) -> bool {
+    let mut header = Vec::new();
+    let read = fs::File::open(path)
+        .and_then(|file| file.take(IMAGE_HEADER_LEN).read_to_end(&mut header))
+        .is_ok();
+
+    read && matches!(
+        image::guess_format(&header),
+        Ok(ImageFormat::Png | ImageFormat::Jpeg)
+    )
+}
+
+/// Unpacks the file at `path` with [`lib::DatasetDescriptor`] when it is a tar.gz or zip
+/// archive, and returns the directory it was unpacked to.
+#[cfg(not(target_family = "wasm"))]
+fn unpack_archive(path: &Path) -> Result<Option<PathBuf>, AlgorithmError> {
+    use lib::{datasets::detect_format, DatasetDescriptor, DatasetFormat};
+
+    match detect_format(path) {
+        Ok(DatasetFormat::TarGz | DatasetFormat::Zip) => {
+            DatasetDescriptor::open(path).map(|dataset| Some(dataset.path))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Archives are not unpacked on wasm32-wasip1, where only image files are classified.
+#[cfg(target_family = "wasm")]
+fn unpack_archive(_path: &Path) -> Result<Option<PathBuf>, AlgorithmError> {
+    Ok(None)
+}
+
+/// Reads the image file at `path` and decodes it with [`image_pixels`].
+fn read_image(path: &Path) -> Result<Vec<u8>, AlgorithmError> {
+    let bytes = fs::read(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => AlgorithmError::MissingFile(path.to_path_buf()),
+        _ => AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        },
+    })?;
+
+    image_pixels(&bytes).map_err(|e| {
+        AlgorithmError::InvalidDataset(format!("Invalid image {}: {}", path.display(), e))
+    })
+}
+
+/// Turns images decoded by [`image_pixels`] into a normalized batch of shape
+/// `[batch_size, 3, 32, 32]`, like the training batches.
+pub fn images_tensor(images: &[Vec<u8>]) -> Tensor<NDBackend, 4> {
+    let device = Default::default();
+    let images = images
+        .iter()
+        .map(|pixels| {
+            let data = TensorData::new(
+                pixels.clone(),
+                Shape::new([CIFAR10_IMAGE_SIZE, CIFAR10_IMAGE_SIZE, 3]),
+            );
+            Tensor::<NDBackend, 3>::from_data(data.convert::<f32>(), &device).permute([2, 0, 1])
+                / 255
+        })
+        .collect();
+
+    normalize_cifar10(Tensor::stack(images, 0))
+}
+
+/// Classifies the image file at `path` with the model found by [`model_source`] from
+/// `model_path`. Returns the probability of every class and the `top_k` most likely ones.
+pub async fn inference(
+    path: &Path,
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Classification, AlgorithmError> {
+    let mut classifications = batch_inference(&[path.to_path_buf()], model_path, top_k).await?;
+
+    Ok(classifications.remove(0))
+}
+
+/// Like [`inference`] for every image file of a batch, in order, with a single forward
+/// pass.
+pub async fn batch_inference(
+    paths: &[PathBuf],
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Vec<Classification>, AlgorithmError> {
+    if paths.is_empty() {
+        return Err(AlgorithmError::InvalidDataset(
+            "The batch has no items".to_string(),
+        ));
+    }
+
+    let images = paths
+        .iter()
+        .map(|path| read_image(path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let source = model_source(model_path)?;
+    let model = build_and_load_model(&source)?;
+
+    let output = softmax(model.forward(images_tensor(&images)), 1);
+    let probabilities = output.into_data().iter::<f32>().collect::<Vec<_>>();
+
+    Classification::batch(&probabilities, &CLASSES, top_k)
+}
+
+/// Classifies every image of the directory or archive at `path`, see [`image_files`],
+/// keeping the name of each file.
+pub async fn directory_inference(
+    path: &Path,
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Vec<ImageClassification>, AlgorithmError> {
+    let files = image_files(path)?;
+    let classifications = batch_inference(&files, model_path, top_k).await?;
+
+    Ok(files
+        .iter()
+        .zip(classifications)
+        .map(|(file, classification)| ImageClassification {
+            image: file
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            classification,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Cnn, MODEL_FILE};
+    use burn::{
+        data::{
+            dataloader::batcher::Batcher,
+            dataset::vision::{Annotation, ImageDatasetItem, PixelDepth},
+        },
+        module::Module,
+        record::{HalfPrecisionSettings, NamedMpkBytesRecorder, Recorder},
+    };
+    use cifar10::data::{ClassificationBatch, ClassificationBatcher};
+    use futures::executor;
+    use image::{Rgb, RgbImage};
+    use std::io::{Cursor, Write};
+
+    fn encode(image: &RgbImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, format).unwrap();
+        bytes.into_inner()
+    }
+
+    fn gradient(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x * 7) as u8, (y * 5) as u8, ((x + y) * 3) as u8])
+        })
+    }
+
+    #[test]
+    fn images_are_preprocessed_like_the_training_batches() {
+        let image = gradient(32, 32);
+        let pixels = image_pixels(&encode(&image, ImageFormat::Png)).unwrap();
+        assert_eq!(pixels, image.as_raw().clone());
+
+        let item = ImageDatasetItem {
+            image: pixels.iter().map(|pixel| PixelDepth::U8(*pixel)).collect(),
+            annotation: Annotation::Label(0),
+            image_path: "gradient.png".to_string(),
+        };
+        let batch: ClassificationBatch<NDBackend> =
+            ClassificationBatcher::new(Default::default()).batch(vec![item]);
+
+        images_tensor(&[pixels])
+            .into_data()
+            .assert_approx_eq(&batch.images.into_data(), 5);
+
+        let resized = image_pixels(&encode(&gradient(64, 48), ImageFormat::Jpeg)).unwrap();
+        assert_eq!(resized.len(), CIFAR10_IMAGE_SIZE * CIFAR10_IMAGE_SIZE * 3);
+        assert!(image_pixels(b"not an image").is_err());
+    }
+
+    #[test]
+    fn directory_images_are_classified_with_the_saved_model() {
+        let dir = std::env::temp_dir().join(format!("cifar10-inference-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let model: Cnn<NDBackend> = Cnn::new(CLASSES.len(), &Default::default());
+        let bytes = NamedMpkBytesRecorder::<HalfPrecisionSettings>::default()
+            .record(model.into_record(), ())
+            .unwrap();
+        fs::write(dir.join(MODEL_FILE), bytes).unwrap();
+        fs::write(
+            dir.join("b.jpg"),
+            encode(&gradient(40, 40), ImageFormat::Jpeg),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("a.png"),
+            encode(&gradient(32, 32), ImageFormat::Png),
+        )
+        .unwrap();
+        // Uploaded images have no extension, and archives are unpacked
+        fs::write(dir.join("c"), encode(&gradient(8, 8), ImageFormat::Png)).unwrap();
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("images/e.png", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        archive
+            .write_all(&encode(&gradient(16, 16), ImageFormat::Png))
+            .unwrap();
+        let archive_name = format!("cifar10-images-{}", std::process::id());
+        fs::write(
+            dir.join(&archive_name),
+            archive.finish().unwrap().into_inner(),
+        )
+        .unwrap();
+
+        let classifications =
+            executor::block_on(directory_inference(&dir, Some(dir.clone()), 2)).unwrap();
+        assert_eq!(
+            classifications
+                .iter()
+                .map(|image| image.image.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.png", "b.jpg", "c", "e.png"]
+        );
+        let unpacked = executor::block_on(directory_inference(
+            &dir.join(&archive_name),
+            Some(dir.clone()),
+            2,
+        ))
+        .unwrap();
+        assert_eq!(unpacked.len(), 1);
+        assert!(!is_image(&dir.join(MODEL_FILE)));
+        for image in &classifications {
+            let classification = &image.classification;
+            let total = classification
+                .probabilities
+                .iter()
+                .map(|class| class.probability)
+                .sum::<f32>();
+            assert!((total - 1.0).abs() < 1e-4);
+            assert_eq!(classification.probabilities.len(), CLASSES.len());
+            assert_eq!(classification.top_k.len(), 2);
+        }
+
+        let single =
+            executor::block_on(inference(&dir.join("b.jpg"), Some(dir.clone()), 2)).unwrap();
+        assert_eq!(single.label, classifications[1].classification.label);
+
+        let err =
+            executor::block_on(inference(&dir.join(MODEL_FILE), Some(dir.clone()), 2)).unwrap_err();
+        assert_eq!(err.kind(), "invalid_dataset");
+        let err =
+            executor::block_on(inference(&dir.join("c.png"), Some(dir.clone()), 2)).unwrap_err();
+        assert_eq!(err.kind(), "missing_file");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
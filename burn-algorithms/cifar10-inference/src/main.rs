@@ -0,0 +1,4 @@
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    cifar10_inference::cli::run(&args[0], &args[1..]);
+}
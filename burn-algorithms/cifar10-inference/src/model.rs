@@ -0,0 +1,148 @@
+// Copied from the https://github.com/ultravioletrs/ai/blob/main/burn-algorithms/cifar10/src/model.rs package
+
+use burn::{
+    nn::{
+        conv::{Conv2d, Conv2dConfig},
+        pool::{MaxPool2d, MaxPool2dConfig},
+        Dropout, DropoutConfig, Linear, LinearConfig, PaddingConfig2d, Relu,
+    },
+    prelude::*,
+    record::{HalfPrecisionSettings, NamedMpkBytesRecorder},
+};
+use lib::{results::DATASETS_DIR, weights::load_model_with, AlgorithmError, ModelSource};
+use std::path::PathBuf;
+
+static STATE_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/model.mpk"));
+
+/// File the CIFAR-10 training saves the model to with `CompactRecorder`.
+pub static MODEL_FILE: &str = "model.mpk";
+
+/// The CIFAR-10 classes in label order, the sorted names of the dataset folders.
+pub const CLASSES: [&str; 10] = [
+    "airplane",
+    "automobile",
+    "bird",
+    "cat",
+    "deer",
+    "dog",
+    "frog",
+    "horse",
+    "ship",
+    "truck",
+];
+pub type NDBackend = burn::backend::ndarray::NdArray<f32>;
+
+#[derive(Module, Debug)]
+pub struct Cnn<B: Backend> {
+    activation: Relu,
+    dropout: Dropout,
+    pool: MaxPool2d,
+    conv1: Conv2d<B>,
+    conv2: Conv2d<B>,
+    conv3: Conv2d<B>,
+    conv4: Conv2d<B>,
+    conv5: Conv2d<B>,
+    conv6: Conv2d<B>,
+    fc1: Linear<B>,
+    fc2: Linear<B>,
+}
+
+impl<B: Backend> Cnn<B> {
+    pub fn new(num_classes: usize, device: &Device<B>) -> Self {
+        let conv1 = Conv2dConfig::new([3, 32], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+        let conv2 = Conv2dConfig::new([32, 32], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+
+        let conv3 = Conv2dConfig::new([32, 64], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+        let conv4 = Conv2dConfig::new([64, 64], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+
+        let conv5 = Conv2dConfig::new([64, 128], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+        let conv6 = Conv2dConfig::new([128, 128], [3, 3])
+            .with_padding(PaddingConfig2d::Same)
+            .init(device);
+
+        let pool = MaxPool2dConfig::new([2, 2]).with_strides([2, 2]).init();
+
+        let fc1 = LinearConfig::new(2048, 128).init(device);
+        let fc2 = LinearConfig::new(128, num_classes).init(device);
+
+        let dropout = DropoutConfig::new(0.3).init();
+
+        Self {
+            activation: Relu::new(),
+            dropout,
+            pool,
+            conv1,
+            conv2,
+            conv3,
+            conv4,
+            conv5,
+            conv6,
+            fc1,
+            fc2,
+        }
+    }
+
+    pub fn forward(&self, x: Tensor<B, 4>) -> Tensor<B, 2> {
+        let x = self.conv1.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.conv2.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.pool.forward(x);
+        let x = self.dropout.forward(x);
+
+        let x = self.conv3.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.conv4.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.pool.forward(x);
+        let x = self.dropout.forward(x);
+
+        let x = self.conv5.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.conv6.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.pool.forward(x);
+        let x = self.dropout.forward(x);
+
+        let x = x.flatten(1, 3);
+
+        let x = self.fc1.forward(x);
+        let x = self.activation.forward(x);
+        let x = self.dropout.forward(x);
+
+        self.fc2.forward(x)
+    }
+}
+
+/// Finds the model at `path`, in the datasets directory, or embedded in the binary.
+pub fn model_source(path: Option<PathBuf>) -> Result<ModelSource, AlgorithmError> {
+    ModelSource::resolve_file_in(path, DATASETS_DIR, MODEL_FILE, STATE_ENCODED)
+}
+
+pub fn build_and_load_model(source: &ModelSource) -> Result<Cnn<NDBackend>, AlgorithmError> {
+    let model: Cnn<NDBackend> = Cnn::new(CLASSES.len(), &Default::default());
+
+    load_model_with(
+        model,
+        source,
+        &NamedMpkBytesRecorder::<HalfPrecisionSettings>::default(),
+        &Default::default(),
+    )
+    .map_err(|e| match e {
+        AlgorithmError::InvalidConfig(message) => AlgorithmError::InvalidConfig(format!(
+            "The model does not match the CIFAR-10 architecture: {}",
+            message
+        )),
+        e => e,
+    })
+}
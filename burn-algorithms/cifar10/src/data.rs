@@ -5,38 +5,28 @@ use burn::{
     },
     prelude::*,
 };
+use lib::preprocessing::normalize_cifar10;
 
-const MEAN: [f32; 3] = [0.4914, 0.48216, 0.44653];
-const STD: [f32; 3] = [0.24703, 0.24349, 0.26159];
-
-#[derive(Clone)]
-pub struct Normalizer<B: Backend> {
-    pub mean: Tensor<B, 4>,
-    pub std: Tensor<B, 4>,
-}
-
-impl<B: Backend> Normalizer<B> {
-    pub fn new(device: &Device<B>) -> Self {
-        let mean = Tensor::<B, 1>::from_floats(MEAN, device).reshape([1, 3, 1, 1]);
-        let std = Tensor::<B, 1>::from_floats(STD, device).reshape([1, 3, 1, 1]);
-        Self { mean, std }
-    }
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Normalizer;
 
+impl Normalizer {
     /// Normalizes the input image according to the CIFAR-10 dataset.
     ///
     /// The input image should be in the range [0, 1].
     /// The output image will be in the range [-1, 1].
     ///
     /// The normalization is done according to the following formula:
-    /// `input = (input - mean) / std`
-    pub fn normalize(&self, input: Tensor<B, 4>) -> Tensor<B, 4> {
-        (input - self.mean.clone()) / self.std.clone()
+    /// `input = (input - mean) / std`, with the mean and standard deviation shared with
+    /// the inference by [`normalize_cifar10`].
+    pub fn normalize<B: Backend>(&self, input: Tensor<B, 4>) -> Tensor<B, 4> {
+        normalize_cifar10(input)
     }
 }
 
 #[derive(Clone)]
 pub struct ClassificationBatcher<B: Backend> {
-    normalizer: Normalizer<B>,
+    normalizer: Normalizer,
     device: B::Device,
 }
 
@@ -49,7 +39,7 @@ pub struct ClassificationBatch<B: Backend> {
 impl<B: Backend> ClassificationBatcher<B> {
     pub fn new(device: B::Device) -> Self {
         Self {
-            normalizer: Normalizer::<B>::new(&device),
+            normalizer: Normalizer,
             device,
         }
     }
@@ -1,50 +1,43 @@
 use burn::data::dataset::vision::ImageFolderDataset;
-use flate2::read::GzDecoder;
-use std::fs::File;
+use lib::AlgorithmError;
 use std::path::{Path, PathBuf};
-use tar::Archive;
 
-pub trait CIFAR10Loader {
-    fn cifar10_train(data_path: &Path) -> Self;
-    fn cifar10_test(data_path: &Path) -> Self;
+pub trait CIFAR10Loader: Sized {
+    fn cifar10_train(data_path: &Path) -> Result<Self, AlgorithmError>;
+    fn cifar10_test(data_path: &Path) -> Result<Self, AlgorithmError>;
 }
 
 impl CIFAR10Loader for ImageFolderDataset {
-    fn cifar10_train(data_path: &Path) -> Self {
-        Self::new_classification(data_path.join("train")).unwrap()
+    fn cifar10_train(data_path: &Path) -> Result<Self, AlgorithmError> {
+        image_folder(&data_path.join("train"))
     }
 
-    fn cifar10_test(data_path: &Path) -> Self {
-        Self::new_classification(data_path.join("test")).unwrap()
+    fn cifar10_test(data_path: &Path) -> Result<Self, AlgorithmError> {
+        image_folder(&data_path.join("test"))
     }
 }
 
-pub fn data_path() -> PathBuf {
-    let data_dir = if cfg!(feature = "cocos") {
-        let datasets_dir = Path::new("datasets");
-        let files = std::fs::read_dir(datasets_dir).expect("Failed to read directory");
-        let tarball_without_ext = files
-            .map(|f| f.expect("Failed to read file").path())
-            .next()
-            .expect("No file found in the directory");
-        let tarball = tarball_without_ext.with_extension("tgz");
-        std::fs::copy(tarball_without_ext.as_path(), &tarball).expect("Failed to copy file");
-        std::fs::remove_file(tarball_without_ext.as_path()).expect("Failed to remove file");
-        let tarball_file = File::open(&tarball).expect("Failed to open file");
-        let tar = GzDecoder::new(tarball_file);
-        let mut archive = Archive::new(tar);
-        archive
-            .unpack(datasets_dir)
-            .expect("Failed to unpack tarball");
-
-        let cifar_dir = datasets_dir.join("cifar10");
+fn image_folder(dir: &Path) -> Result<ImageFolderDataset, AlgorithmError> {
+    if !dir.is_dir() {
+        return Err(AlgorithmError::MissingFile(dir.to_path_buf()));
+    }
 
-        let labels_file = cifar_dir.join("labels.txt");
-        if !labels_file.exists() {
-            panic!("Download the CIFAR-10 dataset from https://s3.amazonaws.com/fast-ai-sample/cifar10.tgz and place it in the data directory");
-        }
+    ImageFolderDataset::new_classification(dir)
+        .map_err(|e| AlgorithmError::InvalidDataset(format!("{}: {}", dir.display(), e)))
+}
 
-        cifar_dir
+/// Returns the CIFAR-10 directories holding the `train` and `test` folders. Under cocos
+/// they come from the uploaded `train` dataset, or from a separate `test` upload.
+pub fn data_paths() -> Result<(PathBuf, PathBuf), AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        let files = lib::dataset_files()?;
+        let train = files.require(lib::DatasetRole::Train)?;
+        let test = match files.open(lib::DatasetRole::Test)? {
+            Some(test) => test,
+            None => train.clone(),
+        };
+
+        Ok((train.find("cifar10")?, test.find("cifar10")?))
     } else {
         let example_dir = Path::new(file!())
             .parent()
@@ -55,11 +48,10 @@ pub fn data_path() -> PathBuf {
 
         let labels_file = cifar_dir.join("labels.txt");
         if !labels_file.exists() {
-            panic!("Download the CIFAR-10 dataset from https://s3.amazonaws.com/fast-ai-sample/cifar10.tgz and place it in the data directory");
+            eprintln!("Download the CIFAR-10 dataset from https://s3.amazonaws.com/fast-ai-sample/cifar10.tgz and place it in the data directory");
+            return Err(AlgorithmError::MissingFile(labels_file));
         }
 
-        cifar_dir
-    };
-
-    data_dir
+        Ok((cifar_dir.clone(), cifar_dir))
+    }
 }
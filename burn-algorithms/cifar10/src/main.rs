@@ -1,46 +1,26 @@
 #[cfg(not(feature = "wgpu"))]
 mod ndarray {
-    use burn::{
-        backend::{
-            ndarray::{NdArray, NdArrayDevice},
-            Autodiff,
-        },
-        optim::{momentum::MomentumConfig, SgdConfig},
+    use burn::backend::{
+        ndarray::{NdArray, NdArrayDevice},
+        Autodiff,
     };
-    use cifar10::training::{train, TrainingConfig};
+    use cifar10::training;
 
     pub fn run() {
-        train::<Autodiff<NdArray>>(
-            TrainingConfig::new(SgdConfig::new().with_momentum(Some(MomentumConfig {
-                momentum: 0.9,
-                dampening: 0.,
-                nesterov: false,
-            }))),
-            NdArrayDevice::default(),
-        );
+        training::run::<Autodiff<NdArray>>(NdArrayDevice::default());
     }
 }
 
 #[cfg(feature = "wgpu")]
 mod wgpu {
-    use burn::{
-        backend::{
-            wgpu::{Wgpu, WgpuDevice},
-            Autodiff,
-        },
-        optim::{momentum::MomentumConfig, SgdConfig},
+    use burn::backend::{
+        wgpu::{Wgpu, WgpuDevice},
+        Autodiff,
     };
-    use cifar10::training::{train, TrainingConfig};
+    use cifar10::training;
 
     pub fn run() {
-        train::<Autodiff<Wgpu>>(
-            TrainingConfig::new(SgdConfig::new().with_momentum(Some(MomentumConfig {
-                momentum: 0.9,
-                dampening: 0.,
-                nesterov: false,
-            }))),
-            WgpuDevice::default(),
-        );
+        training::run::<Autodiff<Wgpu>>(WgpuDevice::default());
     }
 }
 
@@ -1,13 +1,13 @@
 use crate::{
     data::ClassificationBatcher,
-    dataset::{data_path, CIFAR10Loader},
+    dataset::{data_paths, CIFAR10Loader},
     model::Cnn,
 };
 use burn::{
     data::{dataloader::DataLoaderBuilder, dataset::vision::ImageFolderDataset},
-    optim::SgdConfig,
+    optim::{momentum::MomentumConfig, SgdConfig},
     prelude::*,
-    record::CompactRecorder,
+    record::{CompactRecorder, HalfPrecisionSettings, NamedMpkBytesRecorder},
     tensor::backend::AutodiffBackend,
     train::{
         metric::{
@@ -17,6 +17,7 @@ use burn::{
         LearnerBuilder, MetricEarlyStoppingStrategy, StoppingCondition,
     },
 };
+use lib::AlgorithmError;
 
 const NUM_CLASSES: u8 = 10;
 
@@ -49,8 +50,30 @@ fn create_artifact_dir(artifact_dir: &str) {
     std::fs::create_dir_all(artifact_dir).ok();
 }
 
+pub fn run<B: AutodiffBackend>(device: B::Device) {
+    let optimizer = SgdConfig::new().with_momentum(Some(MomentumConfig {
+        momentum: 0.9,
+        dampening: 0.,
+        nesterov: false,
+    }));
+    train::<B>(TrainingConfig::new(optimizer), device);
+}
+
 pub fn train<B: AutodiffBackend>(config: TrainingConfig, device: B::Device) {
+    if let Err(err) = launch::<B>(config, device) {
+        lib::exit_with_error(ARTIFACT_DIR, err);
+    }
+}
+
+fn launch<B: AutodiffBackend>(
+    config: TrainingConfig,
+    device: B::Device,
+) -> Result<(), AlgorithmError> {
     create_artifact_dir(ARTIFACT_DIR);
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(ARTIFACT_DIR)?;
+    }
+    let config = lib::load_config(config)?;
 
     config
         .save(format!("{ARTIFACT_DIR}/config.json"))
@@ -58,20 +81,31 @@ pub fn train<B: AutodiffBackend>(config: TrainingConfig, device: B::Device) {
 
     B::seed(config.seed);
 
+    let model = Cnn::new(NUM_CLASSES.into(), &device);
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &NamedMpkBytesRecorder::<HalfPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
+
     let batcher_train = ClassificationBatcher::<B>::new(device.clone());
     let batcher_valid = ClassificationBatcher::<B::InnerBackend>::new(device.clone());
 
-    let data_path = data_path();
+    let (train_path, test_path) = data_paths()?;
     let dataloader_train = DataLoaderBuilder::new(batcher_train)
         .batch_size(config.batch_size)
         .shuffle(config.seed)
         .num_workers(config.num_workers)
-        .build(ImageFolderDataset::cifar10_train(&data_path));
+        .build(ImageFolderDataset::cifar10_train(&train_path)?);
 
     let dataloader_test = DataLoaderBuilder::new(batcher_valid)
         .batch_size(config.batch_size)
         .num_workers(config.num_workers)
-        .build(ImageFolderDataset::cifar10_test(&data_path));
+        .build(ImageFolderDataset::cifar10_test(&test_path)?);
 
     let learner = if cfg!(feature = "cocos") {
         LearnerBuilder::new(ARTIFACT_DIR)
@@ -90,12 +124,8 @@ pub fn train<B: AutodiffBackend>(config: TrainingConfig, device: B::Device) {
             ))
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
-            .renderer(lib::EmptyMetricsRenderer)
-            .build(
-                Cnn::new(NUM_CLASSES.into(), &device),
-                config.optimizer.init(),
-                config.learning_rate,
-            )
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
+            .build(model, config.optimizer.init(), config.learning_rate)
     } else {
         LearnerBuilder::new(ARTIFACT_DIR)
             .metric_train_numeric(AccuracyMetric::new())
@@ -114,15 +144,17 @@ pub fn train<B: AutodiffBackend>(config: TrainingConfig, device: B::Device) {
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
             .summary()
-            .build(
-                Cnn::new(NUM_CLASSES.into(), &device),
-                config.optimizer.init(),
-                config.learning_rate,
-            )
+            .build(model, config.optimizer.init(), config.learning_rate)
     };
     let model_trained = learner.fit(dataloader_train, dataloader_test);
 
     model_trained
         .save_file(format!("{ARTIFACT_DIR}/model"), &CompactRecorder::new())
         .expect("Trained model should be saved successfully");
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(ARTIFACT_DIR).expect("Failed to write results manifest");
+    }
+
+    Ok(())
 }
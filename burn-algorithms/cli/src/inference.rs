@@ -0,0 +1,20 @@
+// The inference workloads of the single binary, built with the `inference` feature
+
+use crate::usage;
+
+pub static INFERENCE_WORKLOADS: [&str; 6] =
+    ["iris", "winequality", "mnist", "cifar10", "agnews", "imdb"];
+
+/// Runs the command line of the `workload` inference binary with `args`.
+pub fn infer(workload: &str, args: &[String]) {
+    let program = format!("burn-algorithms infer {}", workload);
+    match workload {
+        "iris" => iris_inference::cli::run(&program, args),
+        "winequality" => winequality_inference::cli::run(&program, args),
+        "mnist" => mnist_inference::cli::run(&program, args),
+        "cifar10" => cifar10_inference::cli::run(&program, args),
+        "agnews" => agnews_inference::cli::run(&program, args),
+        "imdb" => imdb_inference::cli::run(&program, args),
+        _ => usage(),
+    }
+}
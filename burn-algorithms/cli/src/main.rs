@@ -0,0 +1,104 @@
+#[cfg(feature = "inference")]
+mod inference;
+
+#[cfg(feature = "wgpu")]
+use burn::backend::wgpu::{Wgpu, WgpuDevice};
+use burn::{
+    backend::{
+        ndarray::{NdArray, NdArrayDevice},
+        Autodiff,
+    },
+    tensor::backend::AutodiffBackend,
+};
+
+static TRAINING_WORKLOADS: [&str; 8] = [
+    "iris",
+    "winequality",
+    "mnist",
+    "cifar10",
+    "agnews",
+    "imdb",
+    "text-classification",
+    "tabular",
+];
+
+pub(crate) fn usage() -> ! {
+    eprintln!(
+        "Usage: burn-algorithms train <workload> [--backend ndarray|wgpu] [config overrides]"
+    );
+    eprintln!("       burn-algorithms infer <workload> <arguments of <workload>-inference>");
+    eprintln!();
+    eprintln!("Training workloads: {}", TRAINING_WORKLOADS.join(", "));
+    #[cfg(feature = "inference")]
+    eprintln!(
+        "Inference workloads: {}",
+        inference::INFERENCE_WORKLOADS.join(", ")
+    );
+    std::process::exit(1);
+}
+
+fn train<B: AutodiffBackend>(workload: &str, device: B::Device) {
+    match workload {
+        "iris" => iris::training::run::<B>(device),
+        "winequality" => winequality::training::run::<B>(device),
+        "mnist" => mnist::training::run::<B>(device),
+        "cifar10" => cifar10::training::run::<B>(device),
+        "agnews" => agnews::training::run::<B>(device),
+        "imdb" => imdb::training::run::<B>(device),
+        "text-classification" => text_classification::training::run::<B>(device),
+        "tabular" => tabular::training::run::<B>(device),
+        _ => usage(),
+    }
+}
+
+/// Trains `workload` on the backend picked with `--backend`. The remaining arguments
+/// override the experiment config.
+fn run_training(workload: &str, args: &[String]) {
+    if !TRAINING_WORKLOADS.contains(&workload) {
+        usage();
+    }
+
+    let mut backend = "ndarray".to_string();
+    let mut config_args = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--backend" => backend = args.next().cloned().unwrap_or_else(|| usage()),
+            _ => match arg.strip_prefix("--backend=") {
+                Some(value) => backend = value.to_string(),
+                None => config_args.push(arg.clone()),
+            },
+        }
+    }
+    lib::config::set_config_args(config_args);
+
+    match backend.as_str() {
+        "ndarray" => train::<Autodiff<NdArray>>(workload, NdArrayDevice::default()),
+        #[cfg(feature = "wgpu")]
+        "wgpu" => train::<Autodiff<Wgpu>>(workload, WgpuDevice::default()),
+        #[cfg(not(feature = "wgpu"))]
+        "wgpu" => {
+            eprintln!("The wgpu backend needs a build with the wgpu feature");
+            std::process::exit(1);
+        }
+        _ => {
+            eprintln!("Unknown backend {}, expected ndarray or wgpu", backend);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [command, workload, rest @ ..] if command == "train" => run_training(workload, rest),
+        #[cfg(feature = "inference")]
+        [command, workload, rest @ ..] if command == "infer" => inference::infer(workload, rest),
+        #[cfg(not(feature = "inference"))]
+        [command, ..] if command == "infer" => {
+            eprintln!("Inference needs a build with the inference feature");
+            std::process::exit(1);
+        }
+        _ => usage(),
+    }
+}
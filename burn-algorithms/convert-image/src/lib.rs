@@ -0,0 +1,22 @@
+use image::GenericImageView;
+use std::path::Path;
+
+/// Reads the first channel of every pixel of an image, row by row, as raw values between
+/// 0 and 255. This is the input `mnist-inference` expects, which normalizes it the same
+/// way as the training batcher.
+pub fn image_pixels(path: impl AsRef<Path>) -> Result<Vec<f32>, image::ImageError> {
+    let img = image::open(path)?;
+
+    let (width, height) = img.dimensions();
+
+    let mut pixel_data: Vec<f32> = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = img.get_pixel(x, y);
+            pixel_data.push(pixel[0] as f32);
+        }
+    }
+
+    Ok(pixel_data)
+}
@@ -0,0 +1,98 @@
+// The command line of the imdb inference, run by `imdb-inference` and by
+// `burn-algorithms infer imdb`
+
+use crate::infer::{batch_inference, inference, ReviewItem};
+use futures::executor;
+use lib::{classification::DEFAULT_TOP_K, results::RESULTS_DIR, AlgorithmError, OutputFormat};
+use std::path::PathBuf;
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} [--model <path>] [--top-k <k>] <review>", program);
+    eprintln!(
+        "       {} [--model <path>] [--top-k <k>] [--format json|csv] --batch [<JSON array> | <CSV or JSON file>]",
+        program
+    );
+    std::process::exit(1);
+}
+
+/// Parses `args`, the arguments after `program`, runs a single or a batch inference and
+/// prints the result, or saves it under cocos. Exits with the code of the error when the
+/// inference fails.
+pub fn run(program: &str, args: &[String]) {
+    let mut model_path = None;
+    let mut format = OutputFormat::default();
+    let mut batch = false;
+    let mut top_k = DEFAULT_TOP_K;
+    let mut input = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--model" => match rest.next() {
+                Some(path) => model_path = Some(PathBuf::from(path)),
+                None => usage(program),
+            },
+            "--format" => match rest.next().map(|format| format.parse()) {
+                Some(Ok(value)) => format = value,
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            "--batch" => batch = true,
+            "--top-k" => match rest.next().map(|k| k.parse()) {
+                Some(Ok(k)) => top_k = k,
+                Some(Err(e)) => {
+                    eprintln!("Invalid --top-k: {}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => usage(program),
+        }
+    }
+
+    let result = match (batch, input) {
+        (true, input) => lib::read_batch::<ReviewItem>(input)
+            .and_then(|items| {
+                let reviews = items
+                    .iter()
+                    .map(|item| item.review.as_str())
+                    .collect::<Vec<_>>();
+                executor::block_on(batch_inference(&reviews, model_path, top_k))
+            })
+            .map(|classifications| lib::write_predictions(&classifications, format)),
+        (false, Some(input)) => {
+            // A review is given as is, or as a {"review": ...} JSON object
+            let review = match serde_json::from_str::<ReviewItem>(input) {
+                Ok(item) => item.review,
+                Err(_) => input.to_string(),
+            };
+            executor::block_on(inference(&review, model_path, top_k)).map(|classification| {
+                serde_json::to_string(&classification).expect("Failed to serialize classification")
+            })
+        }
+        (false, None) => usage(program),
+    };
+    output(result);
+}
+
+fn output(result: Result<String, AlgorithmError>) {
+    match result {
+        Ok(result) => {
+            if cfg!(feature = "cocos") {
+                match lib::save_results(result) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{:}", result.trim_end());
+            }
+        }
+        Err(e) => lib::exit_with_error(RESULTS_DIR, e),
+    };
+}
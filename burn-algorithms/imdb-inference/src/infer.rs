@@ -0,0 +1,129 @@
+use crate::model::{build_and_load_model, model_source};
+use lib::{AlgorithmError, Classification};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// A review to score. In a CSV file it is the `review` column, like in the IMDB dataset,
+/// and the other columns, such as `sentiment`, are ignored.
+#[derive(Deserialize, Debug)]
+pub struct ReviewItem {
+    #[serde(alias = "text")]
+    pub review: String,
+}
+
+/// Tells whether `review` is positive or negative with the model found by
+/// [`model_source`] from `model_path`. Returns the probability of both sentiments and the
+/// `top_k` most likely ones.
+pub async fn inference(
+    review: &str,
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Classification, AlgorithmError> {
+    let mut classifications = batch_inference(&[review], model_path, top_k).await?;
+
+    Ok(classifications.remove(0))
+}
+
+/// Like [`inference`] for every review of a batch, in order, with a single forward pass.
+pub async fn batch_inference(
+    reviews: &[&str],
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Vec<Classification>, AlgorithmError> {
+    if reviews.is_empty() {
+        return Err(AlgorithmError::InvalidDataset(
+            "The batch has no items".to_string(),
+        ));
+    }
+
+    let source = model_source(model_path)?;
+    let classifier = build_and_load_model(&source)?;
+
+    classifier.classify(reviews, top_k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::NDBackend;
+    use burn::{
+        module::Module,
+        nn::transformer::TransformerEncoderConfig,
+        record::{HalfPrecisionSettings, NamedMpkBytesRecorder, Recorder},
+    };
+    use futures::executor;
+    use lib::{
+        config::CONFIG_FILE,
+        text_model::{TextClassificationModel, TextModelConfig, TEXT_MODEL_FILE},
+        tokenizer::{Tokenizer, TOKENIZER_FILE},
+        WordPieceTokenizer,
+    };
+    use std::fs;
+
+    static TOKENIZER_JSON: &[u8] = include_bytes!("../../lib/fixtures/bert-cased/tokenizer.json");
+
+    /// Saves a small untrained model like the training does: `model.mpk` with
+    /// `CompactRecorder`, next to its `config.json` and `tokenizer.json`.
+    fn save_model(dir: &std::path::Path) {
+        let config = serde_json::json!({
+            "dataset": {
+                "text_columns": ["review"],
+                "label_column": "sentiment",
+                "labels": ["negative", "positive"],
+                "train_file": "IMDB Dataset.csv",
+            },
+            "transformer": TransformerEncoderConfig::new(16, 32, 2, 1),
+            "max_seq_length": 32,
+        });
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE), config.to_string()).unwrap();
+        fs::write(dir.join(TOKENIZER_FILE), TOKENIZER_JSON).unwrap();
+
+        let config = TextModelConfig::from_slice(config.to_string().as_bytes()).unwrap();
+        let vocab_size = WordPieceTokenizer::from_tokenizer_json(TOKENIZER_JSON)
+            .unwrap()
+            .vocab_size();
+        let model: TextClassificationModel<NDBackend> =
+            TextClassificationModel::new(&config, 2, vocab_size, &Default::default());
+        let bytes = NamedMpkBytesRecorder::<HalfPrecisionSettings>::default()
+            .record(model.into_record(), ())
+            .unwrap();
+        fs::write(dir.join(TEXT_MODEL_FILE), bytes).unwrap();
+    }
+
+    #[test]
+    fn reviews_are_read_and_scored_as_a_batch() {
+        let dir = std::env::temp_dir().join(format!("imdb-inference-{}", std::process::id()));
+        save_model(&dir);
+        let csv = dir.join("reviews.csv");
+        fs::write(
+            &csv,
+            "review,sentiment\n\"A wonderful, moving film.\",positive\nDull and far too long.,negative\n",
+        )
+        .unwrap();
+
+        let items = lib::read_batch::<ReviewItem>(csv.to_str()).unwrap();
+        let reviews = items
+            .iter()
+            .map(|item| item.review.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(reviews[0], "A wonderful, moving film.");
+
+        let classifications =
+            executor::block_on(batch_inference(&reviews, Some(dir.clone()), 2)).unwrap();
+        assert_eq!(classifications.len(), 2);
+        for classification in &classifications {
+            assert!(["negative", "positive"].contains(&classification.class.as_str()));
+            assert_eq!(classification.top_k.len(), 2);
+            let total = classification.top_k[0].probability + classification.top_k[1].probability;
+            assert!((total - 1.0).abs() < 1e-4);
+        }
+
+        let single = executor::block_on(inference(reviews[1], Some(dir.clone()), 1)).unwrap();
+        assert_eq!(single.label, classifications[1].label);
+        assert!((single.probability - classifications[1].probability).abs() < 1e-4);
+        assert_eq!(single.top_k.len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+use std::{env, fs, path::Path};
+
+/// The files the Iris training saves, embedded when they exist. A binary built
+/// before the model was trained loads them from `--model` or the datasets directory.
+const ARTIFACTS: [&str; 2] = ["model.bin", "config.json"];
+
+fn main() {
+    let out = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let artifacts = Path::new(env!("CARGO_MANIFEST_DIR")).join("../artifacts/iris");
+
+    for artifact in ARTIFACTS {
+        let path = artifacts.join(artifact);
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let data = fs::read(&path).unwrap_or_default();
+        fs::write(Path::new(&out).join(artifact), data)
+            .unwrap_or_else(|e| panic!("Failed to embed {}: {}", artifact, e));
+    }
+}
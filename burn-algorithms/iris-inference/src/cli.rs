@@ -0,0 +1,109 @@
+// The command line of the iris inference, run by `iris-inference` and by
+// `burn-algorithms infer iris`
+
+use crate::infer::{batch_classify, batch_inference, classify, inference, IrisItem};
+use futures::executor;
+use lib::{classification::DEFAULT_TOP_K, results::RESULTS_DIR, AlgorithmError, OutputFormat};
+use std::path::PathBuf;
+
+static EXAMPLE: &str =
+    r#"{"sepal_length": 5.1, "sepal_width": 3.5, "petal_length": 1.4, "petal_width": 0.2}"#;
+
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} [--model <path>] [--probabilities] [--top-k <k>] <JSON input>",
+        program
+    );
+    eprintln!(
+        "       {} [--model <path>] [--probabilities] [--top-k <k>] [--format json|csv] --batch [<JSON array> | <CSV or JSON file>]",
+        program
+    );
+    std::process::exit(1);
+}
+
+/// Parses `args`, the arguments after `program`, runs a single or a batch inference and
+/// prints the result, or saves it under cocos. Exits with the code of the error when the
+/// inference fails.
+pub fn run(program: &str, args: &[String]) {
+    let mut model_path = None;
+    let mut format = OutputFormat::default();
+    let mut batch = false;
+    let mut probabilities = false;
+    let mut top_k = DEFAULT_TOP_K;
+    let mut input = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--model" => match rest.next() {
+                Some(path) => model_path = Some(PathBuf::from(path)),
+                None => usage(program),
+            },
+            "--format" => match rest.next().map(|format| format.parse()) {
+                Some(Ok(value)) => format = value,
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            "--batch" => batch = true,
+            "--probabilities" => probabilities = true,
+            "--top-k" => match rest.next().map(|k| k.parse()) {
+                Some(Ok(k)) => {
+                    probabilities = true;
+                    top_k = k;
+                }
+                Some(Err(e)) => {
+                    eprintln!("Invalid --top-k: {}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => usage(program),
+        }
+    }
+
+    let result = match (batch, input) {
+        (true, input) if probabilities => lib::read_batch::<IrisItem>(input)
+            .and_then(|items| executor::block_on(batch_classify(&items, model_path, top_k)))
+            .map(|classifications| lib::write_predictions(&classifications, format)),
+        (true, input) => lib::read_batch::<IrisItem>(input)
+            .and_then(|items| executor::block_on(batch_inference(&items, model_path)))
+            .map(|classes| lib::write_predictions(&classes, format)),
+        (false, Some(input)) => {
+            lib::read_input(input, EXAMPLE).and_then(|item| match probabilities {
+                true => {
+                    executor::block_on(classify(item, model_path, top_k)).map(|classification| {
+                        serde_json::to_string(&classification)
+                            .expect("Failed to serialize classification")
+                    })
+                }
+                false => {
+                    executor::block_on(inference(item, model_path)).map(|class| class.to_string())
+                }
+            })
+        }
+        (false, None) => usage(program),
+    };
+    output(result);
+}
+
+fn output(result: Result<String, AlgorithmError>) {
+    match result {
+        Ok(result) => {
+            if cfg!(feature = "cocos") {
+                match lib::save_results(result) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{:}", result.trim_end());
+            }
+        }
+        Err(e) => lib::exit_with_error(RESULTS_DIR, e),
+    };
+}
@@ -1,51 +1,100 @@
-use crate::model::{build_and_load_model, NDBackend};
+use crate::model::{build_and_load_model, model_source, NDBackend};
 use burn::tensor::Tensor;
+use lib::{AlgorithmError, Classification};
 use serde::Deserialize;
+use std::path::PathBuf;
 
 #[derive(Deserialize, Debug)]
 pub struct IrisItem {
+    #[serde(alias = "SepalLengthCm")]
     pub sepal_length: f32,
+    #[serde(alias = "SepalWidthCm")]
     pub sepal_width: f32,
+    #[serde(alias = "PetalLengthCm")]
     pub petal_length: f32,
+    #[serde(alias = "PetalWidthCm")]
     pub petal_width: f32,
 }
 
-pub async fn inference(iris_item: IrisItem) -> Result<&'static str, String> {
-    let model = match build_and_load_model() {
-        Ok(model) => Some(model),
-        Err(e) => return Err(e),
-    };
+impl IrisItem {
+    fn features(&self) -> [f32; 4] {
+        [
+            self.sepal_length,
+            self.sepal_width,
+            self.petal_length,
+            self.petal_width,
+        ]
+    }
+}
 
-    let model = match model.as_ref() {
-        Some(model) => model,
-        None => return Err("Model not found".to_string()),
-    };
+/// Names of the classes, in label order.
+pub static CLASSES: [&str; 3] = ["Iris-setosa", "Iris-versicolor", "Iris-virginica"];
 
-    let device = Default::default();
+/// Classifies `iris_item` with the model found by [`model_source`] from `model_path`.
+pub async fn inference(
+    iris_item: IrisItem,
+    model_path: Option<PathBuf>,
+) -> Result<&'static str, AlgorithmError> {
+    let classes = batch_inference(&[iris_item], model_path).await?;
 
-    let input: [f32; 4] = [
-        iris_item.sepal_length,
-        iris_item.sepal_width,
-        iris_item.petal_length,
-        iris_item.petal_width,
-    ];
+    Ok(classes[0])
+}
 
-    let input = Tensor::<NDBackend, 1>::from_floats(input, &device).unsqueeze();
-    let output = model.forward(input);
+/// Classifies every item of a batch, in order, with a single forward pass of the model
+/// found by [`model_source`] from `model_path`.
+pub async fn batch_inference(
+    iris_items: &[IrisItem],
+    model_path: Option<PathBuf>,
+) -> Result<Vec<&'static str>, AlgorithmError> {
+    let classifications = batch_classify(iris_items, model_path, 1).await?;
 
-    let output = burn::tensor::activation::softmax(output, 1);
-    let max_index = output.argmax(1);
+    Ok(classifications
+        .iter()
+        .map(|classification| CLASSES[classification.label])
+        .collect())
+}
 
-    let class: i64 = max_index.into_scalar();
+/// Like [`inference`], with the probability of every class and the `top_k` most likely
+/// ones.
+pub async fn classify(
+    iris_item: IrisItem,
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Classification, AlgorithmError> {
+    let mut classifications = batch_classify(&[iris_item], model_path, top_k).await?;
 
-    Ok(label_to_class(class))
+    Ok(classifications.remove(0))
 }
 
-fn label_to_class(label: i64) -> &'static str {
-    match label {
-        0 => "Iris-setosa",
-        1 => "Iris-versicolor",
-        2 => "Iris-virginica",
-        _ => panic!("Invalid class"),
+/// Like [`batch_inference`], with the probability of every class and the `top_k` most
+/// likely ones for each item.
+pub async fn batch_classify(
+    iris_items: &[IrisItem],
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Vec<Classification>, AlgorithmError> {
+    if iris_items.is_empty() {
+        return Err(AlgorithmError::InvalidDataset(
+            "The batch has no items".to_string(),
+        ));
     }
+
+    let source = model_source(model_path)?;
+    let model = build_and_load_model(&source)?;
+
+    let device = Default::default();
+
+    let input = iris_items
+        .iter()
+        .flat_map(IrisItem::features)
+        .collect::<Vec<_>>();
+
+    let input = Tensor::<NDBackend, 1>::from_floats(input.as_slice(), &device)
+        .reshape([iris_items.len(), 4]);
+    let output = model.forward(input);
+
+    let output = burn::tensor::activation::softmax(output, 1);
+    let probabilities = output.into_data().iter::<f32>().collect::<Vec<_>>();
+
+    Classification::batch(&probabilities, &CLASSES, top_k)
 }
@@ -4,12 +4,45 @@ use burn::{
     module::Module,
     nn::{Linear, LinearConfig, Relu},
     prelude::*,
-    record::{BinBytesRecorder, FullPrecisionSettings, Recorder},
 };
+use lib::{config::CONFIG_FILE, weights::load_model, AlgorithmError, ModelSource};
+use serde::Deserialize;
+use std::path::PathBuf;
 
 pub type NDBackend = burn::backend::ndarray::NdArray<f32>;
 
-static STATE_ENCODED: &[u8] = include_bytes!("../../artifacts/iris/model.bin");
+static STATE_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/model.bin"));
+static CONFIG_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/config.json"));
+
+/// Number of features of an inference input.
+const INPUT_FEATURES: usize = 4;
+
+/// The fields of the training config that shape the model. The other fields saved with
+/// it are ignored.
+#[derive(Deserialize, Debug)]
+pub struct ModelConfig {
+    pub input_feature_len: usize,
+    pub hidden_size: usize,
+}
+
+impl ModelConfig {
+    /// Reads the training config saved next to the model in `source`.
+    pub fn load(source: &ModelSource) -> Result<Self, AlgorithmError> {
+        let bytes = source.sibling(CONFIG_FILE, CONFIG_ENCODED)?;
+        let config: Self = serde_json::from_slice(&bytes).map_err(|e| {
+            AlgorithmError::InvalidConfig(format!("Invalid training {}: {}", CONFIG_FILE, e))
+        })?;
+
+        if config.input_feature_len != INPUT_FEATURES {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The model was trained on {} features but Iris inputs have {}",
+                config.input_feature_len, INPUT_FEATURES
+            )));
+        }
+
+        Ok(config)
+    }
+}
 
 #[derive(Module, Debug)]
 pub struct Model<B: Backend> {
@@ -20,10 +53,18 @@ pub struct Model<B: Backend> {
 }
 
 impl<B: Backend> Model<B> {
-    pub fn new(device: &B::Device) -> Self {
-        let input_layer = LinearConfig::new(4, 128).with_bias(true).init(device);
-        let hidden_layer = LinearConfig::new(128, 128 / 2).with_bias(true).init(device);
-        let output_layer = LinearConfig::new(128 / 2, 3).with_bias(true).init(device);
+    /// Builds the architecture the model was trained with.
+    pub fn new(config: &ModelConfig, device: &B::Device) -> Self {
+        let hidden_size = config.hidden_size;
+        let input_layer = LinearConfig::new(config.input_feature_len, hidden_size)
+            .with_bias(true)
+            .init(device);
+        let hidden_layer = LinearConfig::new(hidden_size, hidden_size / 2)
+            .with_bias(true)
+            .init(device);
+        let output_layer = LinearConfig::new(hidden_size / 2, 3)
+            .with_bias(true)
+            .init(device);
 
         Model {
             input_layer,
@@ -43,14 +84,44 @@ impl<B: Backend> Model<B> {
     }
 }
 
-pub fn build_and_load_model() -> Result<Model<NDBackend>, String> {
-    let model: Model<NDBackend> = Model::new(&Default::default());
-    let record = match BinBytesRecorder::<FullPrecisionSettings>::default()
-        .load(STATE_ENCODED.to_vec(), &Default::default())
-    {
-        Ok(record) => record,
-        Err(e) => return Err(format!("Failed to load model: {}", e)),
-    };
+/// Finds the model at `path`, in the datasets directory, or embedded in the binary.
+pub fn model_source(path: Option<PathBuf>) -> Result<ModelSource, AlgorithmError> {
+    ModelSource::resolve(path, STATE_ENCODED)
+}
+
+/// Builds the model described by the training config saved next to it and loads it.
+pub fn build_and_load_model(source: &ModelSource) -> Result<Model<NDBackend>, AlgorithmError> {
+    let config = ModelConfig::load(source)?;
+    let model: Model<NDBackend> = Model::new(&config, &Default::default());
+
+    load_model(model, source, &Default::default()).map_err(|e| match e {
+        AlgorithmError::InvalidConfig(message) => AlgorithmError::InvalidConfig(format!(
+            "The model does not match the architecture in {}: {}",
+            CONFIG_FILE, message
+        )),
+        e => e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::weights::MODEL_FILE;
+    use std::fs;
 
-    Ok(model.load_record(record))
+    #[test]
+    fn missing_training_config_is_reported() {
+        let dir = std::env::temp_dir().join(format!("iris-inference-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MODEL_FILE), b"model").unwrap();
+
+        let source = model_source(Some(dir.clone())).unwrap();
+        let err = ModelConfig::load(&source).unwrap_err();
+        assert!(matches!(err, AlgorithmError::MissingFile(path) if path == dir.join(CONFIG_FILE)));
+
+        let err = ModelConfig::load(&ModelSource::Embedded(b"model")).err();
+        assert_eq!(err.is_some(), CONFIG_ENCODED.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
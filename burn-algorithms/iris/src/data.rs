@@ -1,4 +1,4 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use burn::{
     data::{
@@ -10,6 +10,7 @@ use burn::{
     },
     prelude::*,
 };
+use lib::AlgorithmError;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct IrisItem {
@@ -36,12 +37,12 @@ pub struct IrisDataset {
     dataset: PartialData,
 }
 
-fn class_label(class: &str) -> i8 {
+fn class_label(class: &str) -> Result<i8, AlgorithmError> {
     match class {
-        "Iris-setosa" => 0,
-        "Iris-versicolor" => 1,
-        "Iris-virginica" => 2,
-        _ => panic!("Invalid class"),
+        "Iris-setosa" => Ok(0),
+        "Iris-versicolor" => Ok(1),
+        "Iris-virginica" => Ok(2),
+        _ => Err(AlgorithmError::InvalidLabel(class.to_string())),
     }
 }
 
@@ -56,52 +57,56 @@ impl Dataset<IrisItem> for IrisDataset {
 }
 
 impl IrisDataset {
-    pub fn train() -> Self {
+    pub fn train() -> Result<Self, AlgorithmError> {
         Self::new("train")
     }
 
-    pub fn test() -> Self {
+    pub fn test() -> Result<Self, AlgorithmError> {
         Self::new("test")
     }
 
-    pub fn new(split: &str) -> Self {
-        let path = IrisDataset::read();
+    pub fn new(split: &str) -> Result<Self, AlgorithmError> {
+        let (path, shared) = IrisDataset::read(split)?;
 
         let mut rdr = csv::ReaderBuilder::new();
         let rdr = rdr.delimiter(b',');
 
-        let dataset = InMemDataset::from_csv(path, rdr).unwrap();
+        let dataset: InMemDataset<IrisItem> =
+            InMemDataset::from_csv(&path, rdr).map_err(|e| AlgorithmError::from_csv(&path, e))?;
+
+        // Labels are checked up front since the batcher cannot fail
+        for item in dataset.iter() {
+            class_label(&item.species)?;
+        }
 
         let len = dataset.len();
 
         let dataset = ShuffledDataset::with_seed(dataset, 42);
 
-        // The dataset from HuggingFace has only train split, so we manually split the train dataset into train
-        // and test in a 80-20 ratio
-        let filtered_dataset = match split {
-            "train" => PartialData::new(dataset, 0, len * 8 / 10),
-            "test" => PartialData::new(dataset, len * 8 / 10, len),
+        // The dataset from HuggingFace has only train split, so unless a separate test dataset
+        // was uploaded we manually split the train dataset into train and test in a 80-20 ratio
+        let filtered_dataset = match (split, shared) {
+            (_, false) => PartialData::new(dataset, 0, len),
+            ("train", true) => PartialData::new(dataset, 0, len * 8 / 10),
+            ("test", true) => PartialData::new(dataset, len * 8 / 10, len),
             _ => panic!("Invalid split type"),
         };
 
-        Self {
+        Ok(Self {
             dataset: filtered_dataset,
-        }
+        })
     }
 
-    fn read() -> PathBuf {
-        let iris_dir = Path::new("datasets");
-        let files = std::fs::read_dir(iris_dir).expect("Failed to read directory");
-        let csv_file = files
-            .map(|f| f.expect("Failed to read file").path())
-            .next()
-            .expect("No file found in the directory");
+    fn read(split: &str) -> Result<(PathBuf, bool), AlgorithmError> {
+        let (csv_file, shared) =
+            lib::dataset_files().and_then(|files| files.split_file(split, "Iris.csv"))?;
 
         if !csv_file.exists() {
-            panic!("Download the Iris dataset from https://www.kaggle.com/datasets/saurabh00007/iriscsv and place it in the data directory");
+            eprintln!("Download the Iris dataset from https://www.kaggle.com/datasets/saurabh00007/iriscsv and place it in the data directory");
+            return Err(AlgorithmError::MissingFile(csv_file));
         }
 
-        csv_file
+        Ok((csv_file, shared))
     }
 }
 
@@ -146,7 +151,10 @@ impl<B: Backend> Batcher<IrisItem, IrisBatch<B>> for IrisBatcher<B> {
             .iter()
             .map(|item| {
                 Tensor::<B, 1, Int>::from_data(
-                    TensorData::from([(class_label(&item.species) as i64).elem::<B::IntElem>()]),
+                    TensorData::from([(class_label(&item.species)
+                        .expect("Labels are checked when the dataset is read")
+                        as i64)
+                        .elem::<B::IntElem>()]),
                     &self.device,
                 )
             })
@@ -6,7 +6,7 @@ use burn::{
     data::{dataloader::DataLoaderBuilder, dataset::Dataset},
     optim::AdamConfig,
     prelude::*,
-    record::{CompactRecorder, NoStdTrainingRecorder},
+    record::{BinBytesRecorder, CompactRecorder, FullPrecisionSettings, NoStdTrainingRecorder},
     tensor::backend::AutodiffBackend,
     train::{
         metric::store::{Aggregate, Direction, Split},
@@ -14,6 +14,7 @@ use burn::{
         LearnerBuilder, MetricEarlyStoppingStrategy, StoppingCondition,
     },
 };
+use lib::AlgorithmError;
 
 #[cfg(feature = "cocos")]
 static ARTIFACT_DIR: &str = "results";
@@ -42,14 +43,33 @@ pub struct ExpConfig {
 }
 
 pub fn run<B: AutodiffBackend>(device: B::Device) {
+    if let Err(err) = train::<B>(device) {
+        lib::exit_with_error(ARTIFACT_DIR, err);
+    }
+}
+
+fn train<B: AutodiffBackend>(device: B::Device) -> Result<(), AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(ARTIFACT_DIR)?;
+    }
+
     let optimizer = AdamConfig::new().with_weight_decay(Some(WeightDecayConfig::new(5e-5)));
-    let config = ExpConfig::new(optimizer);
+    let config = lib::load_config(ExpConfig::new(optimizer))?;
     let model =
         ClassificationModelConfig::new(config.input_feature_len, config.hidden_size).init(&device);
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &BinBytesRecorder::<FullPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
     B::seed(config.seed);
 
-    let train_dataset = IrisDataset::train();
-    let test_dataset = IrisDataset::test();
+    let train_dataset = IrisDataset::train()?;
+    let test_dataset = IrisDataset::test()?;
 
     #[cfg(not(feature = "cocos"))]
     {
@@ -90,7 +110,7 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             ))
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
-            .renderer(lib::EmptyMetricsRenderer)
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
             .build(model, config.optimizer.init(), config.learning_rate)
     } else {
         LearnerBuilder::new(ARTIFACT_DIR)
@@ -125,4 +145,10 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             &NoStdTrainingRecorder::new(),
         )
         .expect("Failed to save trained model");
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(ARTIFACT_DIR).expect("Failed to write results manifest");
+    }
+
+    Ok(())
 }
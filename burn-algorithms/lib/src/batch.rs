@@ -0,0 +1,283 @@
+use crate::{
+    config::CONFIG_FILE,
+    error::AlgorithmError,
+    results::{DATASETS_DIR, MANIFEST_FILE},
+    scaler::SCALER_FILE,
+    tokenizer::TOKENIZER_FILE,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// How batch predictions are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A JSON array with one `{"row": .., "prediction": ..}` object per input row.
+    #[default]
+    Json,
+    /// A CSV file with a `row,prediction` header.
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "Unknown output format {}, expected json or csv",
+                value
+            )),
+        }
+    }
+}
+
+/// The prediction for one row of a batch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Prediction<P> {
+    pub row: usize,
+    pub prediction: P,
+}
+
+/// Reads a single item from the JSON `input`. The error shows `example`, a valid input.
+pub fn read_input<T: DeserializeOwned>(input: &str, example: &str) -> Result<T, AlgorithmError> {
+    serde_json::from_str(input).map_err(|e| {
+        AlgorithmError::InvalidDataset(format!(
+            "Invalid JSON input: {}. Provide a valid JSON input for example: {}",
+            e, example
+        ))
+    })
+}
+
+/// Reads the items of a batch from `input`, which is a JSON array or the path to a JSON
+/// or CSV file. Without `input`, the single CSV or JSON file uploaded to the datasets
+/// directory is read.
+pub fn read_batch<T: DeserializeOwned>(input: Option<&str>) -> Result<Vec<T>, AlgorithmError> {
+    read_batch_in(input, DATASETS_DIR)
+}
+
+pub fn read_batch_in<T: DeserializeOwned>(
+    input: Option<&str>,
+    datasets_dir: impl AsRef<Path>,
+) -> Result<Vec<T>, AlgorithmError> {
+    let path = match input {
+        Some(input) if input.trim_start().starts_with('[') => {
+            return serde_json::from_str(input)
+                .map_err(|e| AlgorithmError::InvalidDataset(format!("Invalid JSON batch: {}", e)));
+        }
+        Some(path) => PathBuf::from(path),
+        None => uploaded_batch(datasets_dir.as_ref())?,
+    };
+
+    if !path.is_file() {
+        return Err(AlgorithmError::MissingFile(path));
+    }
+    match extension(&path).as_deref() {
+        Some("json") => {
+            let data = fs::read(&path).map_err(|source| AlgorithmError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            serde_json::from_slice(&data).map_err(|e| {
+                AlgorithmError::InvalidDataset(format!(
+                    "Invalid JSON batch {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        }
+        _ => read_csv(&path),
+    }
+}
+
+/// Formats the predictions of a batch, in input order.
+pub fn write_predictions<P: Serialize + fmt::Display>(
+    predictions: &[P],
+    format: OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Json => {
+            let rows = predictions
+                .iter()
+                .enumerate()
+                .map(|(row, prediction)| Prediction { row, prediction })
+                .collect::<Vec<_>>();
+            serde_json::to_string(&rows).expect("Failed to serialize predictions")
+        }
+        OutputFormat::Csv => {
+            let mut output = String::from("row,prediction\n");
+            for (row, prediction) in predictions.iter().enumerate() {
+                output.push_str(&format!("{},{}\n", row, prediction));
+            }
+            output
+        }
+    }
+}
+
+/// Reads a CSV file with a header row, separated by commas or, like the Wine Quality
+/// files, by semicolons.
+fn read_csv<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, AlgorithmError> {
+    let data = fs::read_to_string(path).map_err(|source| AlgorithmError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let header = data.lines().next().unwrap_or_default();
+    let delimiter = match header.contains(';') && !header.contains(',') {
+        true => b';',
+        false => b',',
+    };
+
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(data.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|e| AlgorithmError::MalformedCsv {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+}
+
+/// Finds the batch input among the uploaded files, skipping the model artifacts that can
+/// be uploaded next to it.
+fn uploaded_batch(datasets_dir: &Path) -> Result<PathBuf, AlgorithmError> {
+    let entries = fs::read_dir(datasets_dir).map_err(|source| AlgorithmError::Io {
+        path: datasets_dir.to_path_buf(),
+        source,
+    })?;
+
+    let mut inputs = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|source| AlgorithmError::Io {
+                path: datasets_dir.to_path_buf(),
+                source,
+            })?
+            .path();
+        let name = path.file_name().and_then(|name| name.to_str());
+        let artifact = matches!(name, Some(name) if [CONFIG_FILE, SCALER_FILE, TOKENIZER_FILE, MANIFEST_FILE].contains(&name));
+        if path.is_file()
+            && !artifact
+            && matches!(extension(&path).as_deref(), Some("csv" | "json"))
+        {
+            inputs.push(path);
+        }
+    }
+
+    match inputs.len() {
+        0 => Err(AlgorithmError::MissingFile(datasets_dir.join("*.csv"))),
+        1 => Ok(inputs.remove(0)),
+        _ => Err(AlgorithmError::InvalidDataset(format!(
+            "Found {} batch inputs in {}, pass the one to score",
+            inputs.len(),
+            datasets_dir.display()
+        ))),
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        #[serde(alias = "Width")]
+        width: f32,
+        height: f32,
+    }
+
+    #[test]
+    fn batch_is_read_from_json_or_csv() {
+        let dir = std::env::temp_dir().join(format!("lib-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let expected = vec![
+            Item {
+                width: 1.0,
+                height: 2.0,
+            },
+            Item {
+                width: 3.5,
+                height: 4.0,
+            },
+        ];
+
+        let inline = r#"[{"width": 1, "height": 2}, {"width": 3.5, "height": 4}]"#;
+        assert_eq!(
+            read_input::<Item>(r#"{"width": 1, "height": 2}"#, "").unwrap(),
+            expected[0]
+        );
+        let err = read_input::<Item>(inline, r#"{"width": 1, "height": 2}"#).unwrap_err();
+        assert!(matches!(err, AlgorithmError::InvalidDataset(_)));
+        assert_eq!(read_batch_in::<Item>(Some(inline), &dir).unwrap(), expected);
+
+        let json = dir.join("items.json");
+        fs::write(&json, inline).unwrap();
+        assert_eq!(
+            read_batch_in::<Item>(json.to_str(), &dir).unwrap(),
+            expected
+        );
+
+        let csv = dir.join("items.csv");
+        fs::write(&csv, "Id;Width;height\n0;1;2\n1;3.5;4\n").unwrap();
+        assert_eq!(read_batch_in::<Item>(csv.to_str(), &dir).unwrap(), expected);
+
+        fs::write(&csv, "width,height\n1,two\n").unwrap();
+        assert!(matches!(
+            read_batch_in::<Item>(csv.to_str(), &dir),
+            Err(AlgorithmError::MalformedCsv { .. })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn batch_defaults_to_the_single_uploaded_input() {
+        let dir = std::env::temp_dir().join(format!("lib-batch-upload-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE), "{}").unwrap();
+        fs::write(dir.join(TOKENIZER_FILE), "{}").unwrap();
+        fs::write(dir.join("model.bin"), [0u8]).unwrap();
+
+        assert!(matches!(
+            read_batch_in::<Item>(None, &dir),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+
+        fs::write(dir.join("items.csv"), "width,height\n1,2\n").unwrap();
+        assert_eq!(read_batch_in::<Item>(None, &dir).unwrap().len(), 1);
+
+        fs::write(dir.join("more.json"), "[]").unwrap();
+        assert!(matches!(
+            read_batch_in::<Item>(None, &dir),
+            Err(AlgorithmError::InvalidDataset(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn predictions_are_written_per_row() {
+        assert_eq!(
+            write_predictions(&["a", "b"], OutputFormat::Json),
+            r#"[{"row":0,"prediction":"a"},{"row":1,"prediction":"b"}]"#
+        );
+        assert_eq!(
+            write_predictions(&[0.5, 1.0], OutputFormat::Csv),
+            "row,prediction\n0,0.5\n1,1\n"
+        );
+        assert_eq!("csv".parse(), Ok(OutputFormat::Csv));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::AlgorithmError;
+use serde::Serialize;
+use std::fmt;
+
+/// Number of most likely classes a [`Classification`] ranks when no other is asked for.
+pub const DEFAULT_TOP_K: usize = 3;
+
+/// The probability the model gives to one class.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ClassProbability {
+    pub label: usize,
+    pub class: String,
+    pub probability: f32,
+}
+
+/// The full output of a classifier for one input.
+///
+/// Besides the predicted class it keeps the probability of every class, in label order,
+/// and the `top_k` most likely ones, so that a prediction with a low confidence can be
+/// told apart from a confident one.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Classification {
+    pub label: usize,
+    pub class: String,
+    pub probability: f32,
+    pub probabilities: Vec<ClassProbability>,
+    pub top_k: Vec<ClassProbability>,
+}
+
+impl Classification {
+    /// Builds the classification from the softmax output of the model, with the name of
+    /// each class in label order. Ties go to the lowest label, like `argmax`. Fails when
+    /// the model does not output one probability per class.
+    pub fn new(
+        probabilities: &[f32],
+        classes: &[&str],
+        top_k: usize,
+    ) -> Result<Self, AlgorithmError> {
+        if classes.is_empty() || probabilities.len() != classes.len() {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The model outputs {} probabilities for {} classes",
+                probabilities.len(),
+                classes.len()
+            )));
+        }
+
+        let probabilities = probabilities
+            .iter()
+            .zip(classes)
+            .enumerate()
+            .map(|(label, (probability, class))| ClassProbability {
+                label,
+                class: class.to_string(),
+                probability: *probability,
+            })
+            .collect::<Vec<_>>();
+
+        let mut ranked = probabilities.clone();
+        // The sort is stable, so equal probabilities stay in label order
+        ranked.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        ranked.truncate(top_k.max(1));
+        let best = ranked[0].clone();
+
+        Ok(Self {
+            label: best.label,
+            class: best.class,
+            probability: best.probability,
+            probabilities,
+            top_k: ranked,
+        })
+    }
+
+    /// Splits the softmax output of a batch, `classes.len()` values per input, into one
+    /// classification per input.
+    pub fn batch(
+        probabilities: &[f32],
+        classes: &[&str],
+        top_k: usize,
+    ) -> Result<Vec<Self>, AlgorithmError> {
+        if classes.is_empty() || !probabilities.len().is_multiple_of(classes.len()) {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The model outputs {} probabilities, not a multiple of its {} classes",
+                probabilities.len(),
+                classes.len()
+            )));
+        }
+
+        probabilities
+            .chunks(classes.len())
+            .map(|probabilities| Self::new(probabilities, classes, top_k))
+            .collect()
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSES: [&str; 3] = ["a", "b", "c"];
+
+    #[test]
+    fn classification_ranks_the_top_k_classes() {
+        let classification = Classification::new(&[0.2, 0.7, 0.1], &CLASSES, 2).unwrap();
+
+        assert_eq!(classification.label, 1);
+        assert_eq!(classification.class, "b");
+        assert_eq!(classification.probability, 0.7);
+        assert_eq!(classification.probabilities.len(), 3);
+        assert_eq!(classification.probabilities[2].class, "c");
+        assert_eq!(
+            classification
+                .top_k
+                .iter()
+                .map(|class| class.label)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert_eq!(
+            Classification::new(&[0.5, 0.5, 0.0], &CLASSES, 0)
+                .unwrap()
+                .top_k
+                .len(),
+            1
+        );
+        assert_eq!(
+            Classification::new(&[0.5, 0.5, 0.0], &CLASSES, 9)
+                .unwrap()
+                .label,
+            0
+        );
+
+        let err = Classification::new(&[0.5, 0.5], &CLASSES, 1).unwrap_err();
+        assert_eq!(err.kind(), "invalid_config");
+        assert!(Classification::new(&[], &[], 1).is_err());
+    }
+
+    #[test]
+    fn batch_is_split_per_input() {
+        let classifications =
+            Classification::batch(&[0.1, 0.1, 0.8, 0.6, 0.3, 0.1], &CLASSES, 1).unwrap();
+
+        assert_eq!(
+            classifications
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["c", "a"]
+        );
+        assert!(Classification::batch(&[0.1, 0.1, 0.8, 0.6], &CLASSES, 1).is_err());
+        assert!(Classification::batch(&[0.1], &[], 1).is_err());
+    }
+}
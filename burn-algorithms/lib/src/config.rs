@@ -0,0 +1,272 @@
+use crate::{error::AlgorithmError, results::DATASETS_DIR};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Experiment config uploaded next to the datasets, and the name the effective config is
+/// saved under in the artifact directory.
+pub static CONFIG_FILE: &str = "config.json";
+
+/// Prefix of the environment variables overriding config fields, for example
+/// `BURN_CONFIG_NUM_EPOCHS=10`. Nested fields are separated by a double underscore, as in
+/// `BURN_CONFIG_OPTIMIZER__WEIGHT_DECAY__PENALTY=1e-4`.
+pub static CONFIG_ENV_PREFIX: &str = "BURN_CONFIG_";
+
+static CONFIG_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Makes [`load_config`] read `args` instead of the process arguments. Used by binaries
+/// that consume their own arguments before training, such as a subcommand CLI. Only the
+/// first call has an effect.
+pub fn set_config_args(args: Vec<String>) {
+    CONFIG_ARGS.get_or_init(|| args);
+}
+
+/// Builds the experiment config of a training binary, starting from `default`.
+///
+/// Fields are overridden, from lowest to highest precedence, by the JSON file passed as
+/// the first argument or with `--config <path>` (otherwise `config.json` in the datasets
+/// directory, if uploaded), then by `BURN_CONFIG_*` environment variables, then by
+/// `--<field> <value>` arguments. Nested fields use dots on the command line, as in
+/// `--optimizer.weight_decay.penalty 1e-4`. Arguments are read from the process, unless
+/// [`set_config_args`] was called.
+pub fn load_config<C: Serialize + DeserializeOwned>(default: C) -> Result<C, AlgorithmError> {
+    let args = match CONFIG_ARGS.get() {
+        Some(args) => args.clone(),
+        None => std::env::args().skip(1).collect(),
+    };
+    load_config_from(default, args, std::env::vars(), DATASETS_DIR)
+}
+
+pub fn load_config_from<C: Serialize + DeserializeOwned>(
+    default: C,
+    args: impl IntoIterator<Item = String>,
+    vars: impl IntoIterator<Item = (String, String)>,
+    datasets_dir: impl AsRef<Path>,
+) -> Result<C, AlgorithmError> {
+    let mut config = serde_json::to_value(default).map_err(invalid)?;
+    let args = parse_args(args)?;
+
+    let file = args.file.or_else(|| {
+        let path = datasets_dir.as_ref().join(CONFIG_FILE);
+        path.is_file().then_some(path)
+    });
+    if let Some(path) = file {
+        let data = fs::read(&path).map_err(|source| AlgorithmError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let value: Value = serde_json::from_slice(&data)
+            .map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+        merge(&mut config, value, "")?;
+    }
+
+    let mut vars = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix(CONFIG_ENV_PREFIX)?;
+            Some((key.to_lowercase().replace("__", "."), value))
+        })
+        .collect::<Vec<_>>();
+    vars.sort();
+    for (key, value) in vars.into_iter().chain(args.overrides) {
+        set(&mut config, &key, parse_value(&value))?;
+    }
+
+    serde_json::from_value(config).map_err(invalid)
+}
+
+/// The config file and the `--<field> <value>` overrides passed on the command line.
+struct ConfigArgs {
+    file: Option<PathBuf>,
+    overrides: Vec<(String, String)>,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<ConfigArgs, AlgorithmError> {
+    let mut file = None;
+    let mut overrides = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            match file {
+                None => file = Some(PathBuf::from(arg)),
+                Some(_) => return Err(invalid(format!("Unexpected argument {}", arg))),
+            }
+            continue;
+        };
+
+        let (name, value) = match name.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => match args.next() {
+                Some(value) => (name.to_string(), value),
+                None => return Err(invalid(format!("Missing value for --{}", name))),
+            },
+        };
+
+        if name == "config" {
+            file = Some(PathBuf::from(value));
+        } else {
+            overrides.push((name.replace('-', "_"), value));
+        }
+    }
+
+    Ok(ConfigArgs { file, overrides })
+}
+
+/// Overlays `value` on `config`, rejecting fields the config does not have.
+fn merge(config: &mut Value, value: Value, prefix: &str) -> Result<(), AlgorithmError> {
+    match (config, value) {
+        (Value::Object(config), Value::Object(value)) => {
+            for (key, value) in value {
+                let path = format!("{}{}", prefix, key);
+                match config.get_mut(&key) {
+                    Some(field) => merge(field, value, &format!("{}.", path))?,
+                    None => return Err(invalid(format!("Unknown config field {}", path))),
+                }
+            }
+            Ok(())
+        }
+        (config, value) => {
+            *config = value;
+            Ok(())
+        }
+    }
+}
+
+/// Sets the field at the dotted `key`. Fields of an optional config that is currently
+/// unset are created on the way.
+fn set(config: &mut Value, key: &str, value: Value) -> Result<(), AlgorithmError> {
+    let mut current = config;
+    let mut created = false;
+    for field in key.split('.') {
+        if current.is_null() {
+            *current = Value::Object(Map::new());
+            created = true;
+        }
+        let object = current
+            .as_object_mut()
+            .ok_or_else(|| invalid(format!("Config field {} is not an object", key)))?;
+        if !created && !object.contains_key(field) {
+            return Err(invalid(format!("Unknown config field {}", key)));
+        }
+        current = object.entry(field).or_insert(Value::Null);
+    }
+
+    *current = value;
+    Ok(())
+}
+
+/// Reads an override as JSON when it is valid JSON, and as a string otherwise.
+fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+fn invalid(message: impl ToString) -> AlgorithmError {
+    AlgorithmError::InvalidConfig(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Decay {
+        penalty: f64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Optimizer {
+        weight_decay: Option<Decay>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ExpConfig {
+        optimizer: Optimizer,
+        num_epochs: usize,
+        learning_rate: f64,
+        seed: u64,
+    }
+
+    fn default() -> ExpConfig {
+        ExpConfig {
+            optimizer: Optimizer { weight_decay: None },
+            num_epochs: 100,
+            learning_rate: 5e-3,
+            seed: 42,
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn overrides_apply_in_order() {
+        let dir = std::env::temp_dir().join(format!("lib-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(CONFIG_FILE),
+            r#"{"num_epochs": 10, "learning_rate": 0.1, "seed": 1}"#,
+        )
+        .unwrap();
+
+        let vars = vec![
+            ("BURN_CONFIG_LEARNING_RATE".to_string(), "0.2".to_string()),
+            ("BURN_CONFIG_SEED".to_string(), "2".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ];
+        let config = load_config_from(
+            default(),
+            args(&["--seed", "3", "--optimizer.weight-decay.penalty=0.5"]),
+            vars,
+            &dir,
+        )
+        .unwrap();
+        assert_eq!(config.num_epochs, 10);
+        assert_eq!(config.learning_rate, 0.2);
+        assert_eq!(config.seed, 3);
+        assert_eq!(config.optimizer.weight_decay, Some(Decay { penalty: 0.5 }));
+
+        fs::write(dir.join("other.json"), r#"{"num_epochs": 20}"#).unwrap();
+        let path = dir.join("other.json").display().to_string();
+        let config = load_config_from(default(), args(&[&path]), vec![], &dir).unwrap();
+        assert_eq!(config.num_epochs, 20);
+        assert_eq!(config.seed, 42);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_and_invalid_fields() {
+        let dir = std::env::temp_dir().join("lib-config-missing");
+        let err = load_config_from(default(), args(&["--epochs", "3"]), vec![], &dir).unwrap_err();
+        assert_eq!(err.kind(), "invalid_config");
+
+        let err =
+            load_config_from(default(), args(&["--num-epochs", "ten"]), vec![], &dir).unwrap_err();
+        assert_eq!(err.kind(), "invalid_config");
+
+        let err = load_config_from(default(), args(&["--seed"]), vec![], &dir).unwrap_err();
+        assert!(err.to_string().contains("Missing value for --seed"));
+    }
+
+    #[test]
+    fn unrelated_environment_variables_are_ignored() {
+        let dir = std::env::temp_dir().join("lib-config-missing");
+        let vars = vec![
+            ("CONFIG_SEED".to_string(), "2".to_string()),
+            ("CONFIG_PATH".to_string(), "/etc/app".to_string()),
+            ("XDG_CONFIG_HOME".to_string(), "/root/.config".to_string()),
+            ("BURN_CONFIG_SEED".to_string(), "7".to_string()),
+        ];
+
+        let config = load_config_from(default(), args(&[]), vars, &dir).unwrap();
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.num_epochs, 100);
+    }
+}
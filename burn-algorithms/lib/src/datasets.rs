@@ -0,0 +1,831 @@
+use crate::{
+    config::CONFIG_FILE, encryption::RESULTS_PUBLIC_KEY_FILE, error::AlgorithmError,
+    results::DATASETS_DIR,
+};
+use burn::{module::Module, record::Recorder, tensor::backend::Backend};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    error::Error,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+use tar::Archive;
+
+pub use crate::tokenizer::TOKENIZER_FILE;
+
+/// Optional file in the datasets directory mapping roles to uploaded files.
+pub static DATASETS_MANIFEST_FILE: &str = "manifest.json";
+
+/// Optional file in the datasets directory describing the columns of a tabular dataset.
+pub static SCHEMA_FILE: &str = "schema.json";
+
+/// Environment variable overriding where archives are unpacked.
+pub static DATASETS_SCRATCH_ENV: &str = "DATASETS_SCRATCH_DIR";
+
+/// Environment variable holding the path of a local `tokenizer.json`.
+pub static TOKENIZER_PATH_ENV: &str = "TOKENIZER_PATH";
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+const SNIFF_LEN: usize = 8192;
+
+/// Format of an uploaded dataset, detected from its content rather than its name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatasetFormat {
+    TarGz,
+    Zip,
+    Gzip,
+    Csv,
+}
+
+/// An uploaded dataset and where its content can be read from.
+///
+/// `source` is never modified. Archives are unpacked and gzip files decompressed into a
+/// scratch directory, in which case `path` points there. For plain CSV files `path` is
+/// the source itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatasetDescriptor {
+    pub source: PathBuf,
+    pub format: DatasetFormat,
+    pub path: PathBuf,
+}
+
+impl DatasetDescriptor {
+    /// Opens `source` using the scratch directory from [`scratch_dir`].
+    pub fn open(source: impl AsRef<Path>) -> Result<Self, AlgorithmError> {
+        Self::open_in(source, scratch_dir())
+    }
+
+    /// Detects the format of `source` and unpacks it below `scratch` if needed.
+    pub fn open_in(
+        source: impl AsRef<Path>,
+        scratch: impl AsRef<Path>,
+    ) -> Result<Self, AlgorithmError> {
+        let source = source.as_ref().to_path_buf();
+        let format = detect_format(&source)?;
+        let file_name = source.file_name().ok_or_else(|| {
+            AlgorithmError::InvalidDataset(format!("Invalid dataset path {}", source.display()))
+        })?;
+        let target = scratch.as_ref().join(file_name);
+        let open = || File::open(&source).map_err(|e| AlgorithmError::from_io(&source, e));
+        let unpack_error = |e: &dyn Error| {
+            AlgorithmError::InvalidDataset(format!("Cannot unpack {}: {}", source.display(), e))
+        };
+
+        let path = match format {
+            DatasetFormat::Csv => source.clone(),
+            DatasetFormat::TarGz => {
+                reset_dir(&target)?;
+                Archive::new(GzDecoder::new(open()?))
+                    .unpack(&target)
+                    .map_err(|e| unpack_error(&e))?;
+                target
+            }
+            DatasetFormat::Zip => {
+                reset_dir(&target)?;
+                zip::ZipArchive::new(open()?)
+                    .and_then(|mut archive| archive.extract(&target))
+                    .map_err(|e| unpack_error(&e))?;
+                target
+            }
+            DatasetFormat::Gzip => {
+                fs::create_dir_all(scratch.as_ref())
+                    .map_err(|e| AlgorithmError::from_io(scratch.as_ref(), e))?;
+                let target = match source.extension().and_then(|e| e.to_str()) {
+                    Some("gz") => target.with_extension(""),
+                    _ => target,
+                };
+                let mut output =
+                    File::create(&target).map_err(|e| AlgorithmError::from_io(&target, e))?;
+                io::copy(&mut GzDecoder::new(open()?), &mut output)
+                    .map_err(|e| unpack_error(&e))?;
+                target
+            }
+        };
+
+        Ok(Self {
+            source,
+            format,
+            path,
+        })
+    }
+
+    /// Returns the data file of a single-file dataset, or the file called `name` inside
+    /// an archive.
+    pub fn file(&self, name: &str) -> Result<PathBuf, AlgorithmError> {
+        match self.format {
+            DatasetFormat::Csv | DatasetFormat::Gzip => Ok(self.path.clone()),
+            DatasetFormat::TarGz | DatasetFormat::Zip => self.find(name),
+        }
+    }
+
+    /// Returns the file or directory called `name`, searching the unpacked content
+    /// breadth first so that the shallowest match wins.
+    pub fn find(&self, name: &str) -> Result<PathBuf, AlgorithmError> {
+        if self.path.file_name().and_then(|n| n.to_str()) == Some(name) {
+            return Ok(self.path.clone());
+        }
+
+        let mut dirs = vec![self.path.clone()];
+        while !dirs.is_empty() {
+            let mut next = Vec::new();
+            for dir in dirs.iter().filter(|d| d.is_dir()) {
+                let mut entries = list_dir(dir)?;
+                entries.sort();
+
+                for entry in entries {
+                    if entry.file_name().and_then(|n| n.to_str()) == Some(name) {
+                        return Ok(entry);
+                    }
+                    if entry.is_dir() {
+                        next.push(entry);
+                    }
+                }
+            }
+            dirs = next;
+        }
+
+        Err(AlgorithmError::MissingFile(self.source.join(name)))
+    }
+}
+
+/// Detects the dataset format from the leading bytes of the file at `path`.
+pub fn detect_format(path: impl AsRef<Path>) -> Result<DatasetFormat, AlgorithmError> {
+    let path = path.as_ref();
+    let read = |gzip: bool| {
+        let file = File::open(path)?;
+        match gzip {
+            true => read_head(GzDecoder::new(file)),
+            false => read_head(file),
+        }
+    };
+    let head = read(false).map_err(|e| AlgorithmError::from_io(path, e))?;
+
+    if head.starts_with(GZIP_MAGIC) {
+        let inner = read(true).map_err(|e| {
+            AlgorithmError::InvalidDataset(format!("Cannot unpack {}: {}", path.display(), e))
+        })?;
+        return match inner.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) {
+            Some(magic) if magic == TAR_MAGIC => Ok(DatasetFormat::TarGz),
+            _ => Ok(DatasetFormat::Gzip),
+        };
+    }
+    if head.starts_with(ZIP_MAGIC) || head.starts_with(ZIP_EMPTY_MAGIC) {
+        return Ok(DatasetFormat::Zip);
+    }
+    if is_csv(&head) {
+        return Ok(DatasetFormat::Csv);
+    }
+
+    Err(AlgorithmError::InvalidDataset(format!(
+        "Unsupported dataset format for {}",
+        path.display()
+    )))
+}
+
+/// Role an uploaded file plays for the algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetRole {
+    Train,
+    Test,
+    Tokenizer,
+    Pretrained,
+}
+
+impl DatasetRole {
+    pub const ALL: [DatasetRole; 4] = [
+        DatasetRole::Train,
+        DatasetRole::Test,
+        DatasetRole::Tokenizer,
+        DatasetRole::Pretrained,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatasetRole::Train => "train",
+            DatasetRole::Test => "test",
+            DatasetRole::Tokenizer => "tokenizer",
+            DatasetRole::Pretrained => "pretrained",
+        }
+    }
+}
+
+impl std::fmt::Display for DatasetRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Uploaded files resolved by role.
+///
+/// Roles are read from `manifest.json` in the datasets directory when present, for
+/// example `{"train": "iris-train.csv", "test": "iris-test.csv"}`. Otherwise a file
+/// takes the role whose name appears in its file name, separated by `-`, `_` or `.`,
+/// such as `train.csv` or `iris_test.csv`. A single upload without a role name is used
+/// for training.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DatasetFiles {
+    roles: BTreeMap<DatasetRole, PathBuf>,
+}
+
+impl DatasetFiles {
+    pub fn read(dir: impl AsRef<Path>) -> Result<Self, AlgorithmError> {
+        let dir = dir.as_ref();
+        let manifest = dir.join(DATASETS_MANIFEST_FILE);
+        if manifest.exists() {
+            return Self::from_manifest(dir, &manifest);
+        }
+
+        let mut roles = BTreeMap::new();
+        let mut unnamed = Vec::new();
+        for file in list_files(dir)? {
+            let matched: Vec<DatasetRole> = DatasetRole::ALL
+                .into_iter()
+                .filter(|role| has_role_name(&file, *role))
+                .collect();
+
+            match matched.as_slice() {
+                [] => unnamed.push(file),
+                [role] => {
+                    if let Some(other) = roles.insert(*role, file.clone()) {
+                        return Err(AlgorithmError::InvalidDataset(format!(
+                            "Both {} and {} look like the {} dataset, add {} to choose",
+                            other.display(),
+                            file.display(),
+                            role,
+                            DATASETS_MANIFEST_FILE
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(AlgorithmError::InvalidDataset(format!(
+                        "{} matches several dataset roles, add {} to choose",
+                        file.display(),
+                        DATASETS_MANIFEST_FILE
+                    )))
+                }
+            }
+        }
+
+        if let Entry::Vacant(train) = roles.entry(DatasetRole::Train) {
+            match unnamed.as_slice() {
+                [] => {}
+                [file] => {
+                    train.insert(file.clone());
+                }
+                _ => {
+                    return Err(AlgorithmError::InvalidDataset(format!(
+                        "Several datasets found in {}, name them by role or add {}",
+                        dir.display(),
+                        DATASETS_MANIFEST_FILE
+                    )))
+                }
+            }
+        }
+
+        Ok(Self { roles })
+    }
+
+    fn from_manifest(dir: &Path, manifest: &Path) -> Result<Self, AlgorithmError> {
+        let bytes = fs::read(manifest).map_err(|e| AlgorithmError::from_io(manifest, e))?;
+        let names: BTreeMap<DatasetRole, String> = serde_json::from_slice(&bytes).map_err(|e| {
+            AlgorithmError::InvalidDataset(format!("Invalid {}: {}", manifest.display(), e))
+        })?;
+
+        let mut roles = BTreeMap::new();
+        for (role, name) in names {
+            let relative = Path::new(&name);
+            if !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                return Err(AlgorithmError::InvalidDataset(format!(
+                    "Invalid {} dataset path {}",
+                    role, name
+                )));
+            }
+
+            let path = dir.join(relative);
+            if !path.is_file() {
+                return Err(AlgorithmError::MissingFile(path));
+            }
+            roles.insert(role, path);
+        }
+
+        Ok(Self { roles })
+    }
+
+    /// Path of the uploaded file for `role`, as uploaded.
+    pub fn get(&self, role: DatasetRole) -> Option<&Path> {
+        self.roles.get(&role).map(PathBuf::as_path)
+    }
+
+    /// Opens the dataset for `role`, if one was uploaded.
+    pub fn open(&self, role: DatasetRole) -> Result<Option<DatasetDescriptor>, AlgorithmError> {
+        self.get(role).map(DatasetDescriptor::open).transpose()
+    }
+
+    /// Returns the data file for the `train` or `test` split, found as `name` inside
+    /// archives, and whether it still has to be divided between both splits. That is
+    /// the case when no separate test dataset was uploaded.
+    pub fn split_file(&self, split: &str, name: &str) -> Result<(PathBuf, bool), AlgorithmError> {
+        let shared = self.get(DatasetRole::Test).is_none();
+        let role = match (split, shared) {
+            ("train", _) | ("test", true) => DatasetRole::Train,
+            ("test", false) => DatasetRole::Test,
+            _ => {
+                return Err(AlgorithmError::InvalidDataset(format!(
+                    "Invalid split {}",
+                    split
+                )))
+            }
+        };
+
+        Ok((self.require(role)?.file(name)?, shared))
+    }
+
+    /// Opens the dataset for `role`, failing when it was not uploaded.
+    pub fn require(&self, role: DatasetRole) -> Result<DatasetDescriptor, AlgorithmError> {
+        self.open(role)?.ok_or_else(|| {
+            AlgorithmError::InvalidDataset(format!("No {} dataset found in {}", role, DATASETS_DIR))
+        })
+    }
+}
+
+/// Resolves the files uploaded to the cocos `datasets` directory by role.
+pub fn dataset_files() -> Result<DatasetFiles, AlgorithmError> {
+    DatasetFiles::read(DATASETS_DIR)
+}
+
+/// Finds the train and test CSV files of a dataset. They are read from `data_dir`, or
+/// from the uploaded datasets when it is `None`: `train_file` inside the `train` upload,
+/// and `test_file` inside a separate `test` upload, where it defaults to `train_file`,
+/// or else inside the `train` upload. Without a test file, the caller splits the train
+/// file between both sets.
+pub fn train_test_files(
+    train_file: &str,
+    test_file: Option<&str>,
+    data_dir: Option<&Path>,
+) -> Result<(PathBuf, Option<PathBuf>), AlgorithmError> {
+    match data_dir {
+        Some(dir) => Ok((dir.join(train_file), test_file.map(|name| dir.join(name)))),
+        None => {
+            let files = dataset_files()?;
+            let train = files.require(DatasetRole::Train)?;
+            let test = match files.open(DatasetRole::Test)? {
+                Some(test) => Some(test.file(test_file.unwrap_or(train_file))?),
+                None => test_file.map(|name| train.file(name)).transpose()?,
+            };
+
+            Ok((train.file(train_file)?, test))
+        }
+    }
+}
+
+/// Reads the header and every record of the CSV file at `path`.
+pub fn read_csv_records(
+    path: &Path,
+    delimiter: char,
+) -> Result<(csv::StringRecord, Vec<csv::StringRecord>), AlgorithmError> {
+    let delimiter = u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| {
+            AlgorithmError::InvalidConfig(format!(
+                "The delimiter {:?} is not an ASCII character",
+                delimiter
+            ))
+        })?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_path(path)
+        .map_err(|e| AlgorithmError::from_csv(path, e.into()))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| AlgorithmError::from_csv(path, e.into()))?
+        .clone();
+    let records = reader
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AlgorithmError::from_csv(path, e.into()))?;
+
+    Ok((headers, records))
+}
+
+/// Starts `model` from the uploaded `pretrained` weights, read with `recorder`, when the
+/// data provider supplied them.
+pub fn load_pretrained<B, M, R>(
+    model: M,
+    recorder: &R,
+    device: &B::Device,
+) -> Result<M, AlgorithmError>
+where
+    B: Backend,
+    M: Module<B>,
+    R: Recorder<B, LoadArgs = Vec<u8>>,
+{
+    match dataset_files()?.get(DatasetRole::Pretrained) {
+        Some(path) => {
+            let bytes = fs::read(path).map_err(|e| AlgorithmError::from_io(path, e))?;
+            let record = recorder.load(bytes, device).map_err(|e| {
+                AlgorithmError::InvalidDataset(format!(
+                    "Invalid pretrained weights {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            Ok(model.load_record(record))
+        }
+        None => Ok(model),
+    }
+}
+
+/// Where a tokenizer is loaded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenizerSource {
+    File(PathBuf),
+    Embedded(&'static [u8]),
+}
+
+/// Finds the tokenizer of a text model without network access: the file in
+/// `TOKENIZER_PATH`, then the uploaded `tokenizer` dataset, then the `embedded` bytes
+/// when they are not empty. Returns `None` when there is no tokenizer at all.
+pub fn tokenizer_source(
+    embedded: &'static [u8],
+) -> Result<Option<TokenizerSource>, AlgorithmError> {
+    tokenizer_source_in(
+        std::env::var_os(TOKENIZER_PATH_ENV).map(PathBuf::from),
+        DATASETS_DIR,
+        embedded,
+    )
+}
+
+pub fn tokenizer_source_in(
+    path: Option<PathBuf>,
+    datasets_dir: impl AsRef<Path>,
+    embedded: &'static [u8],
+) -> Result<Option<TokenizerSource>, AlgorithmError> {
+    if let Some(path) = path {
+        if !path.is_file() {
+            return Err(AlgorithmError::MissingFile(path));
+        }
+        return Ok(Some(TokenizerSource::File(path)));
+    }
+
+    if datasets_dir.as_ref().is_dir() {
+        let files = DatasetFiles::read(datasets_dir)?;
+        if let Some(path) = files.get(DatasetRole::Tokenizer) {
+            // A plain tokenizer.json is not a known dataset format, only archives are opened
+            let path = match detect_format(path) {
+                Ok(DatasetFormat::TarGz | DatasetFormat::Zip | DatasetFormat::Gzip) => {
+                    DatasetDescriptor::open(path)?.file(TOKENIZER_FILE)?
+                }
+                _ => path.to_path_buf(),
+            };
+            return Ok(Some(TokenizerSource::File(path)));
+        }
+    }
+
+    if !embedded.is_empty() {
+        return Ok(Some(TokenizerSource::Embedded(embedded)));
+    }
+
+    Ok(None)
+}
+
+/// Directory archives are unpacked into, `DATASETS_SCRATCH_DIR` or a directory in the
+/// system temporary directory.
+pub fn scratch_dir() -> PathBuf {
+    match std::env::var(DATASETS_SCRATCH_ENV) {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => std::env::temp_dir().join("cocos-datasets"),
+    }
+}
+
+fn read_head(reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    reader.take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+fn is_csv(head: &[u8]) -> bool {
+    if head.is_empty() || head.contains(&0) {
+        return false;
+    }
+
+    // The sample may end in the middle of a multi-byte character.
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+
+    text.lines()
+        .next()
+        .is_some_and(|header| header.contains([',', ';', '\t']))
+}
+
+/// Regular files in `dir` sorted by name, without hidden files, the datasets manifest,
+/// the experiment config and the results public key.
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>, AlgorithmError> {
+    let mut files = list_dir(dir)?;
+    files.retain(|f| {
+        f.is_file()
+            && !is_hidden(f)
+            && !f.ends_with(RESULTS_PUBLIC_KEY_FILE)
+            && !f.ends_with(DATASETS_MANIFEST_FILE)
+            && !f.ends_with(CONFIG_FILE)
+            && !f.ends_with(SCHEMA_FILE)
+    });
+    files.sort();
+
+    Ok(files)
+}
+
+/// Entries of `dir`, in no particular order.
+fn list_dir(dir: &Path) -> Result<Vec<PathBuf>, AlgorithmError> {
+    fs::read_dir(dir)
+        .and_then(|entries| entries.map(|e| e.map(|e| e.path())).collect())
+        .map_err(|e| AlgorithmError::from_io(dir, e))
+}
+
+fn has_role_name(path: &Path, role: DatasetRole) -> bool {
+    path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+        n.to_lowercase()
+            .split(['-', '_', '.'])
+            .any(|token| token == role.as_str())
+    })
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+fn reset_dir(dir: &Path) -> Result<(), AlgorithmError> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(|e| AlgorithmError::from_io(dir, e))?;
+    }
+    fs::create_dir_all(dir).map_err(|e| AlgorithmError::from_io(dir, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const CSV: &str = "sepal_length,species\n5.1,Iris-setosa\n";
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("lib-datasets-{}-{}", name, std::process::id()));
+        reset_dir(&dir).unwrap();
+        dir
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn tar_gz(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, data).unwrap();
+        gzip(&builder.into_inner().unwrap())
+    }
+
+    fn zip(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn detect_format_uses_content_not_name() {
+        let dir = test_dir("detect");
+        let cases = [
+            (
+                "a",
+                tar_gz("iris/iris.csv", CSV.as_bytes()),
+                DatasetFormat::TarGz,
+            ),
+            ("b.csv", zip("iris.csv", CSV.as_bytes()), DatasetFormat::Zip),
+            ("c.zip", gzip(CSV.as_bytes()), DatasetFormat::Gzip),
+            ("d.tgz", CSV.as_bytes().to_vec(), DatasetFormat::Csv),
+        ];
+        for (name, data, format) in cases {
+            fs::write(dir.join(name), data).unwrap();
+            assert_eq!(detect_format(dir.join(name)).unwrap(), format);
+        }
+
+        fs::write(dir.join("e"), [0u8, 1, 2, 3]).unwrap();
+        let err = detect_format(dir.join("e")).unwrap_err();
+        assert_eq!(err.kind(), "invalid_dataset");
+        let err = detect_format(dir.join("missing")).unwrap_err();
+        assert_eq!(err.path(), Some(dir.join("missing").as_path()));
+        assert_eq!(err.kind(), "missing_file");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn open_unpacks_without_touching_source() {
+        let dir = test_dir("open");
+        let scratch = dir.join("scratch");
+        fs::write(dir.join("upload"), tar_gz("iris/iris.csv", CSV.as_bytes())).unwrap();
+        fs::write(dir.join("wine"), zip("data/wine.csv", CSV.as_bytes())).unwrap();
+        fs::write(dir.join("plain.csv.gz"), gzip(CSV.as_bytes())).unwrap();
+        fs::write(dir.join("iris.csv"), CSV).unwrap();
+
+        let tarball = DatasetDescriptor::open_in(dir.join("upload"), &scratch).unwrap();
+        assert_eq!(tarball.path, scratch.join("upload"));
+        assert_eq!(tarball.find("iris").unwrap(), scratch.join("upload/iris"));
+        let csv = tarball.find("iris.csv").unwrap();
+        assert_eq!(fs::read_to_string(csv).unwrap(), CSV);
+        assert!(dir.join("upload").exists());
+
+        let zipped = DatasetDescriptor::open_in(dir.join("wine"), &scratch).unwrap();
+        assert_eq!(
+            fs::read_to_string(zipped.find("wine.csv").unwrap()).unwrap(),
+            CSV
+        );
+        assert!(zipped.find("missing.csv").is_err());
+        assert_eq!(
+            zipped.file("wine.csv").unwrap(),
+            zipped.find("wine.csv").unwrap()
+        );
+
+        let gzipped = DatasetDescriptor::open_in(dir.join("plain.csv.gz"), &scratch).unwrap();
+        assert_eq!(gzipped.path, scratch.join("plain.csv"));
+        assert_eq!(fs::read_to_string(&gzipped.path).unwrap(), CSV);
+
+        let plain = DatasetDescriptor::open_in(dir.join("iris.csv"), &scratch).unwrap();
+        assert_eq!(plain.path, dir.join("iris.csv"));
+        assert_eq!(plain.find("iris.csv").unwrap(), dir.join("iris.csv"));
+        assert_eq!(plain.file("Iris.csv").unwrap(), dir.join("iris.csv"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dataset_files_resolve_roles_by_name() {
+        let dir = test_dir("names");
+        fs::write(dir.join(".hidden"), CSV).unwrap();
+        fs::write(dir.join("results.pub"), "00").unwrap();
+        fs::write(dir.join("iris_train.csv"), CSV).unwrap();
+        fs::write(dir.join("Iris-Test.csv"), CSV).unwrap();
+        fs::write(dir.join("tokenizer.json"), "{}").unwrap();
+        fs::create_dir(dir.join("pretrained")).unwrap();
+
+        let files = DatasetFiles::read(&dir).unwrap();
+        assert_eq!(
+            files.get(DatasetRole::Train),
+            Some(dir.join("iris_train.csv").as_path())
+        );
+        assert_eq!(
+            files.get(DatasetRole::Test),
+            Some(dir.join("Iris-Test.csv").as_path())
+        );
+        assert_eq!(
+            files.get(DatasetRole::Tokenizer),
+            Some(dir.join("tokenizer.json").as_path())
+        );
+        assert_eq!(files.get(DatasetRole::Pretrained), None);
+
+        fs::write(dir.join("train-extra.csv"), CSV).unwrap();
+        assert!(DatasetFiles::read(&dir).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dataset_files_use_single_unnamed_upload_for_training() {
+        let dir = test_dir("single");
+        fs::write(dir.join("iris"), CSV).unwrap();
+        fs::write(dir.join(CONFIG_FILE), "{}").unwrap();
+        fs::write(dir.join(SCHEMA_FILE), "{}").unwrap();
+        let files = DatasetFiles::read(&dir).unwrap();
+        assert_eq!(
+            files.get(DatasetRole::Train),
+            Some(dir.join("iris").as_path())
+        );
+        assert_eq!(
+            files.require(DatasetRole::Train).unwrap().format,
+            DatasetFormat::Csv
+        );
+        assert!(files.require(DatasetRole::Test).is_err());
+        assert_eq!(
+            files.split_file("test", "iris.csv").unwrap(),
+            (dir.join("iris"), true)
+        );
+
+        fs::write(dir.join("wine"), CSV).unwrap();
+        assert!(DatasetFiles::read(&dir).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn dataset_files_read_manifest() {
+        let dir = test_dir("manifest");
+        fs::write(dir.join("a.csv"), CSV).unwrap();
+        fs::write(dir.join("b.csv"), CSV).unwrap();
+        fs::write(
+            dir.join(DATASETS_MANIFEST_FILE),
+            r#"{"train": "b.csv", "test": "a.csv"}"#,
+        )
+        .unwrap();
+
+        let files = DatasetFiles::read(&dir).unwrap();
+        assert_eq!(
+            files.get(DatasetRole::Train),
+            Some(dir.join("b.csv").as_path())
+        );
+        assert_eq!(
+            files.get(DatasetRole::Test),
+            Some(dir.join("a.csv").as_path())
+        );
+
+        for manifest in [
+            r#"{"train": "missing.csv"}"#,
+            r#"{"train": "../a.csv"}"#,
+            r#"{"validation": "a.csv"}"#,
+        ] {
+            fs::write(dir.join(DATASETS_MANIFEST_FILE), manifest).unwrap();
+            assert!(DatasetFiles::read(&dir).is_err());
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn tokenizer_source_falls_back_in_order() {
+        let dir = test_dir("tokenizer");
+        let datasets = dir.join("datasets");
+        fs::create_dir_all(&datasets).unwrap();
+        fs::write(dir.join("local.json"), "{}").unwrap();
+
+        assert_eq!(tokenizer_source_in(None, &datasets, b"").unwrap(), None);
+        assert_eq!(
+            tokenizer_source_in(None, &datasets, b"{}").unwrap(),
+            Some(TokenizerSource::Embedded(b"{}"))
+        );
+
+        fs::write(datasets.join("bert-tokenizer.json"), "{}").unwrap();
+        assert_eq!(
+            tokenizer_source_in(None, &datasets, b"{}").unwrap(),
+            Some(TokenizerSource::File(datasets.join("bert-tokenizer.json")))
+        );
+        assert_eq!(
+            tokenizer_source_in(Some(dir.join("local.json")), &datasets, b"{}").unwrap(),
+            Some(TokenizerSource::File(dir.join("local.json")))
+        );
+
+        let err = tokenizer_source_in(Some(dir.join("missing.json")), &datasets, b"").unwrap_err();
+        assert_eq!(err.kind(), "missing_file");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn csv_records_are_read_from_the_data_directory() {
+        let dir = test_dir("csv");
+        fs::write(dir.join("wine.csv"), "pH;quality\n3.2;6\n3.5;5\n").unwrap();
+
+        let (train, test) = train_test_files("wine.csv", Some("test.csv"), Some(&dir)).unwrap();
+        assert_eq!(train, dir.join("wine.csv"));
+        assert_eq!(test, Some(dir.join("test.csv")));
+
+        let (headers, records) = read_csv_records(&train, ';').unwrap();
+        assert_eq!(headers.iter().collect::<Vec<_>>(), vec!["pH", "quality"]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(&records[1][0], "3.5");
+
+        let err = read_csv_records(&test.unwrap(), ';').unwrap_err();
+        assert_eq!(err.kind(), "missing_file");
+        let err = read_csv_records(&train, 'é').unwrap_err();
+        assert_eq!(err.kind(), "invalid_config");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
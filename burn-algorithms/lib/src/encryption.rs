@@ -0,0 +1,265 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+/// Environment variable holding the hex encoded X25519 public key of the data consumer.
+pub static RESULTS_PUBLIC_KEY_ENV: &str = "RESULTS_PUBLIC_KEY";
+
+/// File in the datasets directory holding the data consumer public key.
+pub static RESULTS_PUBLIC_KEY_FILE: &str = "results.pub";
+
+/// File the data consumer keeps its secret key in.
+pub static RESULTS_SECRET_KEY_FILE: &str = "results.key";
+
+/// Extension appended to the name of every sealed artifact.
+pub static SEALED_EXTENSION: &str = "sealed";
+
+/// Media type recorded in the manifest for sealed artifacts.
+pub static SEALED_MEDIA_TYPE: &str = "application/vnd.cocos.sealed";
+
+const MAGIC: &[u8; 4] = b"SEAL";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 32 + NONCE_LEN;
+const KDF_INFO: &[u8] = b"cocos-results";
+
+/// Generates a key pair for a data consumer. The secret stays with the consumer, the
+/// public key is handed to the computation.
+pub fn generate_keypair() -> (StaticSecret, PublicKey) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    (secret, public)
+}
+
+/// Encrypts `plaintext` so that only the holder of the secret key matching `recipient`
+/// can read it.
+///
+/// The output is `SEAL`, a version byte, an ephemeral X25519 public key, a nonce and the
+/// ChaCha20-Poly1305 ciphertext. The key is derived with HKDF-SHA256 from the shared
+/// secret, salted with both public keys.
+pub fn seal(recipient: &PublicKey, plaintext: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(recipient);
+
+    let cipher = cipher(shared.as_bytes(), &ephemeral_public, recipient)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| "Failed to encrypt artifact")?;
+
+    let mut sealed = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.push(VERSION);
+    sealed.extend_from_slice(ephemeral_public.as_bytes());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+
+    Ok(sealed)
+}
+
+/// Decrypts an artifact produced by [`seal`].
+pub fn open(secret: &StaticSecret, sealed: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if sealed.len() < HEADER_LEN || &sealed[..MAGIC.len()] != MAGIC {
+        return Err("Not a sealed artifact".into());
+    }
+    if sealed[MAGIC.len()] != VERSION {
+        return Err(format!(
+            "Unsupported sealed artifact version {}",
+            sealed[MAGIC.len()]
+        )
+        .into());
+    }
+
+    let key_start = MAGIC.len() + 1;
+    let nonce_start = key_start + 32;
+    let mut ephemeral_public = [0u8; 32];
+    ephemeral_public.copy_from_slice(&sealed[key_start..nonce_start]);
+    let ephemeral_public = PublicKey::from(ephemeral_public);
+
+    let shared = secret.diffie_hellman(&ephemeral_public);
+    let cipher = cipher(
+        shared.as_bytes(),
+        &ephemeral_public,
+        &PublicKey::from(secret),
+    )?;
+
+    cipher
+        .decrypt(
+            Nonce::from_slice(&sealed[nonce_start..HEADER_LEN]),
+            &sealed[HEADER_LEN..],
+        )
+        .map_err(|_| "Failed to decrypt artifact, wrong key or corrupted data".into())
+}
+
+/// Generates a key pair and writes it as hex to `results.key` and `results.pub` in `dir`.
+/// The secret key is only readable by its owner, and an existing one is never replaced.
+pub fn write_keypair(dir: impl AsRef<Path>) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let (secret, public) = generate_keypair();
+    let secret_path = dir.join(RESULTS_SECRET_KEY_FILE);
+    let public_path = dir.join(RESULTS_PUBLIC_KEY_FILE);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options
+        .open(&secret_path)
+        .and_then(|mut file| file.write_all(hex::encode(secret.to_bytes()).as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", secret_path.display(), e))?;
+    std::fs::write(&public_path, hex::encode(public.as_bytes()))?;
+
+    Ok((secret_path, public_path))
+}
+
+/// Decrypts the sealed file at `path` with the hex encoded secret key in `key_path`.
+pub fn open_file(
+    key_path: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let secret = secret_key_from_hex(&std::fs::read_to_string(key_path)?)?;
+    open(&secret, &std::fs::read(path)?)
+}
+
+pub fn public_key_from_hex(value: &str) -> Result<PublicKey, Box<dyn Error>> {
+    Ok(PublicKey::from(key_bytes(value)?))
+}
+
+pub fn secret_key_from_hex(value: &str) -> Result<StaticSecret, Box<dyn Error>> {
+    Ok(StaticSecret::from(key_bytes(value)?))
+}
+
+/// Looks up the data consumer public key, first in the `RESULTS_PUBLIC_KEY` environment
+/// variable and then in `results.pub` inside `datasets_dir`.
+pub fn recipient_key(datasets_dir: impl AsRef<Path>) -> Result<Option<PublicKey>, Box<dyn Error>> {
+    if let Ok(value) = std::env::var(RESULTS_PUBLIC_KEY_ENV) {
+        return Ok(Some(public_key_from_hex(&value)?));
+    }
+
+    let path = datasets_dir.as_ref().join(RESULTS_PUBLIC_KEY_FILE);
+    if path.exists() {
+        return Ok(Some(public_key_from_hex(&std::fs::read_to_string(path)?)?));
+    }
+
+    Ok(None)
+}
+
+fn key_bytes(value: &str) -> Result<[u8; 32], Box<dyn Error>> {
+    let bytes = hex::decode(value.trim())?;
+    bytes
+        .try_into()
+        .map_err(|_| "Key must be 32 bytes long".into())
+}
+
+fn cipher(
+    shared: &[u8],
+    ephemeral: &PublicKey,
+    recipient: &PublicKey,
+) -> Result<ChaCha20Poly1305, Box<dyn Error>> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral.as_bytes());
+    salt[32..].copy_from_slice(recipient.as_bytes());
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(KDF_INFO, &mut key)
+        .map_err(|_| "Failed to derive encryption key")?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let (secret, public) = generate_keypair();
+        let sealed = seal(&public, b"model weights").unwrap();
+
+        assert_ne!(&sealed[HEADER_LEN..], b"model weights");
+        assert_eq!(open(&secret, &sealed).unwrap(), b"model weights");
+    }
+
+    #[test]
+    fn open_rejects_wrong_key_and_tampering() {
+        let (_, public) = generate_keypair();
+        let (other, _) = generate_keypair();
+        let mut sealed = seal(&public, b"predictions").unwrap();
+        assert!(open(&other, &sealed).is_err());
+
+        let (secret, public) = generate_keypair();
+        sealed = seal(&public, b"predictions").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(open(&secret, &sealed).is_err());
+        assert!(open(&secret, b"SEAL").is_err());
+    }
+
+    #[test]
+    fn keys_parse_from_hex() {
+        let (secret, public) = generate_keypair();
+        let parsed = public_key_from_hex(&format!("{}\n", hex::encode(public.as_bytes()))).unwrap();
+        assert_eq!(parsed, public);
+        let parsed = secret_key_from_hex(&hex::encode(secret.to_bytes())).unwrap();
+        assert_eq!(PublicKey::from(&parsed), public);
+        assert!(public_key_from_hex("abcd").is_err());
+    }
+
+    #[test]
+    fn open_file_uses_written_keypair() {
+        let dir = std::env::temp_dir().join(format!("lib-encryption-{}", std::process::id()));
+        let (secret_path, public_path) = write_keypair(&dir).unwrap();
+
+        let public = recipient_key(&dir).unwrap().unwrap();
+        assert_eq!(
+            public,
+            public_key_from_hex(&std::fs::read_to_string(public_path).unwrap()).unwrap()
+        );
+        std::fs::write(
+            dir.join("results.txt.sealed"),
+            seal(&public, b"42").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            open_file(secret_path, dir.join("results.txt.sealed")).unwrap(),
+            b"42"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_key_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("lib-keypair-{}", std::process::id()));
+        let (secret_path, _) = write_keypair(&dir).unwrap();
+
+        let mode = std::fs::metadata(&secret_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(write_keypair(&dir).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
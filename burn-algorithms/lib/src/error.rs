@@ -0,0 +1,237 @@
+use crate::results::write_results_manifest;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// File in the results directory describing why an algorithm failed.
+pub static ERROR_FILE: &str = "error.json";
+
+/// Errors an algorithm reports instead of panicking while reading its datasets or config.
+///
+/// Every variant has a stable `kind` and exit code, so a failed computation can be told
+/// apart from a crash without reading its logs:
+///
+/// | Kind              | Exit code |
+/// |-------------------|-----------|
+/// | `missing_file`    | 10        |
+/// | `malformed_csv`   | 11        |
+/// | `invalid_label`   | 12        |
+/// | `invalid_dataset` | 13        |
+/// | `integrity`       | 14        |
+/// | `io`              | 15        |
+/// | `invalid_config`  | 16        |
+/// | `tokenizer`       | 17        |
+///
+/// Any other failure still panics, which Rust reports with exit code 101.
+#[derive(Debug)]
+pub enum AlgorithmError {
+    /// A dataset file the algorithm needs does not exist.
+    MissingFile(PathBuf),
+    /// A CSV file could not be parsed into records.
+    MalformedCsv { path: PathBuf, message: String },
+    /// A record holds a label the algorithm does not know.
+    InvalidLabel(String),
+    /// An uploaded dataset could not be resolved or has the wrong layout.
+    InvalidDataset(String),
+    /// The uploaded datasets do not match the expected hashes.
+    Integrity(String),
+    /// A dataset file could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// The experiment config could not be read or has fields of the wrong type.
+    InvalidConfig(String),
+    /// No tokenizer was found for a text model, or it could not be loaded.
+    Tokenizer(String),
+}
+
+/// The machine readable form of an [`AlgorithmError`], written to `error.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub kind: String,
+    pub exit_code: i32,
+    pub message: String,
+    pub path: Option<String>,
+}
+
+impl AlgorithmError {
+    /// Classifies an error returned while reading the CSV file at `path`. The csv crate
+    /// reports every failure as `io::ErrorKind::Other`, so the file is opened again to
+    /// tell a missing or unreadable file apart from malformed content.
+    pub fn from_csv(path: impl AsRef<Path>, err: io::Error) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match fs::File::open(&path) {
+            Err(source) if source.kind() == io::ErrorKind::NotFound => Self::MissingFile(path),
+            Err(source) => Self::Io { path, source },
+            Ok(_) => Self::MalformedCsv {
+                path,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Classifies an error returned while accessing the file or directory at `path`.
+    pub fn from_io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match source.kind() {
+            io::ErrorKind::NotFound => Self::MissingFile(path),
+            _ => Self::Io { path, source },
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MissingFile(_) => "missing_file",
+            Self::MalformedCsv { .. } => "malformed_csv",
+            Self::InvalidLabel(_) => "invalid_label",
+            Self::InvalidDataset(_) => "invalid_dataset",
+            Self::Integrity(_) => "integrity",
+            Self::Io { .. } => "io",
+            Self::InvalidConfig(_) => "invalid_config",
+            Self::Tokenizer(_) => "tokenizer",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::MissingFile(_) => 10,
+            Self::MalformedCsv { .. } => 11,
+            Self::InvalidLabel(_) => 12,
+            Self::InvalidDataset(_) => 13,
+            Self::Integrity(_) => 14,
+            Self::Io { .. } => 15,
+            Self::InvalidConfig(_) => 16,
+            Self::Tokenizer(_) => 17,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::MissingFile(path) | Self::MalformedCsv { path, .. } | Self::Io { path, .. } => {
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn report(&self) -> ErrorReport {
+        ErrorReport {
+            kind: self.kind().to_string(),
+            exit_code: self.exit_code(),
+            message: self.to_string(),
+            path: self.path().map(|p| p.display().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for AlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFile(path) => write!(f, "Dataset file {} not found", path.display()),
+            Self::MalformedCsv { path, message } => {
+                write!(f, "Malformed CSV file {}: {}", path.display(), message)
+            }
+            Self::InvalidLabel(label) => write!(f, "Invalid label {:?}", label),
+            Self::InvalidDataset(message) => write!(f, "Invalid dataset: {}", message),
+            Self::Integrity(message) => write!(f, "Dataset integrity check failed: {}", message),
+            Self::Io { path, source } => write!(f, "Failed to read {}: {}", path.display(), source),
+            Self::InvalidConfig(message) => write!(f, "Invalid config: {}", message),
+            Self::Tokenizer(message) => write!(f, "Tokenizer error: {}", message),
+        }
+    }
+}
+
+impl Error for AlgorithmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the report of `err` to `error.json` in `dir`.
+pub fn write_error(dir: impl AsRef<Path>, err: &AlgorithmError) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(dir.as_ref())?;
+    let path = dir.as_ref().join(ERROR_FILE);
+    fs::write(&path, serde_json::to_vec_pretty(&err.report())?)?;
+    Ok(path)
+}
+
+/// Records `err` in the results directory, signs the results written so far and exits
+/// with the exit code of the error.
+pub fn exit_with_error(results_dir: impl AsRef<Path>, err: AlgorithmError) -> ! {
+    eprintln!("Error: {}", err);
+
+    let results_dir = results_dir.as_ref();
+    if let Err(e) = write_error(results_dir, &err) {
+        eprintln!("Failed to write {}: {}", ERROR_FILE, e);
+    } else if let Err(e) = write_results_manifest(results_dir) {
+        eprintln!("Failed to write results manifest: {}", e);
+    }
+
+    std::process::exit(err.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_have_distinct_kinds_and_codes() {
+        let dir = std::env::temp_dir().join(format!("lib-error-kinds-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("iris.csv"), "a,b\n1\n").unwrap();
+        let csv_error = || io::Error::other("CSV deserialize error");
+
+        let errors = [
+            AlgorithmError::from_csv(dir.join("missing.csv"), csv_error()),
+            AlgorithmError::from_csv(dir.join("iris.csv"), csv_error()),
+            AlgorithmError::InvalidLabel("Iris-unknown".to_string()),
+            AlgorithmError::InvalidDataset("no train dataset".to_string()),
+            AlgorithmError::Integrity("hash mismatch".to_string()),
+            AlgorithmError::Io {
+                path: dir.join("iris.csv"),
+                source: io::ErrorKind::PermissionDenied.into(),
+            },
+            AlgorithmError::InvalidConfig("unknown field epochs".to_string()),
+            AlgorithmError::Tokenizer("no tokenizer found".to_string()),
+        ];
+
+        for (i, err) in errors.iter().enumerate() {
+            for other in &errors[i + 1..] {
+                assert_ne!(err.kind(), other.kind());
+                assert_ne!(err.exit_code(), other.exit_code());
+            }
+        }
+        assert!(matches!(errors[0], AlgorithmError::MissingFile(_)));
+        assert!(matches!(errors[1], AlgorithmError::MalformedCsv { .. }));
+
+        let missing = AlgorithmError::from_io(dir.join("missing"), io::ErrorKind::NotFound.into());
+        assert_eq!(missing.path(), Some(dir.join("missing").as_path()));
+        assert_eq!(missing.kind(), "missing_file");
+        let denied = AlgorithmError::from_io(&dir, io::ErrorKind::PermissionDenied.into());
+        assert_eq!(denied.kind(), "io");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_error_records_report() {
+        let dir = std::env::temp_dir().join(format!("lib-error-{}", std::process::id()));
+        let err = AlgorithmError::MalformedCsv {
+            path: PathBuf::from("datasets/iris.csv"),
+            message: "found record with 3 fields".to_string(),
+        };
+
+        let path = write_error(&dir, &err).unwrap();
+        let report: ErrorReport = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(report.kind, "malformed_csv");
+        assert_eq!(report.exit_code, 11);
+        assert_eq!(report.path.as_deref(), Some("datasets/iris.csv"));
+        assert!(report.message.contains("3 fields"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{error::AlgorithmError, results::DATASETS_DIR};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+/// Environment variable holding the expected dataset hashes as a JSON object mapping
+/// file names to hex SHA-256 digests. Read at run time first, then at build time.
+pub static DATASET_HASHES_ENV: &str = "DATASET_HASHES";
+
+/// File in the results directory recording the hash of every uploaded dataset.
+pub static DATASET_HASHES_FILE: &str = "datasets.json";
+
+/// Expected hashes embedded when the algorithm was built.
+const BUILD_DATASET_HASHES: Option<&str> = option_env!("DATASET_HASHES");
+
+/// Size and digest of an uploaded dataset file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetHash {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Hashes of every uploaded dataset file, as recorded in the results.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetHashes {
+    pub files: Vec<DatasetHash>,
+    /// Whether the files were checked against a list of expected hashes.
+    pub verified: bool,
+}
+
+/// Returns the expected hashes from `DATASET_HASHES` at run time, or the ones embedded at
+/// build time, if any.
+pub fn expected_hashes() -> Result<Option<BTreeMap<String, String>>, AlgorithmError> {
+    let value = match std::env::var(DATASET_HASHES_ENV) {
+        Ok(value) => value,
+        Err(_) => match BUILD_DATASET_HASHES {
+            Some(value) => value.to_string(),
+            None => return Ok(None),
+        },
+    };
+
+    let hashes = serde_json::from_str(&value)
+        .map_err(|e| AlgorithmError::Integrity(format!("Invalid {}: {}", DATASET_HASHES_ENV, e)))?;
+    Ok(Some(hashes))
+}
+
+/// Computes the SHA-256 of every file uploaded to `dir`, sorted by name.
+pub fn hash_datasets(dir: impl AsRef<Path>) -> Result<Vec<DatasetHash>, AlgorithmError> {
+    let dir = dir.as_ref();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| AlgorithmError::from_io(dir, e))? {
+        let path = entry.map_err(|e| AlgorithmError::from_io(dir, e))?.path();
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) if path.is_file() && !name.starts_with('.') => name.to_string(),
+            _ => continue,
+        };
+
+        let mut hasher = Sha256::new();
+        let size = File::open(&path)
+            .and_then(|mut file| io::copy(&mut file, &mut hasher))
+            .map_err(|e| AlgorithmError::from_io(&path, e))?;
+        files.push(DatasetHash {
+            name,
+            size,
+            sha256: hex::encode(hasher.finalize()),
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(files)
+}
+
+/// Checks that the uploaded files are exactly the expected ones, with the expected digests.
+pub fn check_hashes(
+    files: &[DatasetHash],
+    expected: &BTreeMap<String, String>,
+) -> Result<(), AlgorithmError> {
+    for file in files {
+        match expected.get(&file.name) {
+            Some(sha256) if sha256.eq_ignore_ascii_case(&file.sha256) => {}
+            Some(sha256) => {
+                return Err(AlgorithmError::Integrity(format!(
+                    "Dataset {} has SHA-256 {}, expected {}",
+                    file.name, file.sha256, sha256
+                )))
+            }
+            None => {
+                return Err(AlgorithmError::Integrity(format!(
+                    "Dataset {} is not an expected dataset",
+                    file.name
+                )))
+            }
+        }
+    }
+
+    for name in expected.keys() {
+        if !files.iter().any(|f| &f.name == name) {
+            return Err(AlgorithmError::Integrity(format!(
+                "Expected dataset {} was not uploaded",
+                name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Hashes the uploaded datasets, records them in `datasets.json` inside `results_dir` and
+/// checks them against the expected hashes. The hashes are recorded even when the check
+/// fails.
+pub fn verify_datasets(results_dir: impl AsRef<Path>) -> Result<DatasetHashes, AlgorithmError> {
+    verify_datasets_in(DATASETS_DIR, results_dir, expected_hashes()?)
+}
+
+pub fn verify_datasets_in(
+    dir: impl AsRef<Path>,
+    results_dir: impl AsRef<Path>,
+    expected: Option<BTreeMap<String, String>>,
+) -> Result<DatasetHashes, AlgorithmError> {
+    let hashes = DatasetHashes {
+        files: hash_datasets(dir)?,
+        verified: expected.is_some(),
+    };
+
+    let path = results_dir.as_ref().join(DATASET_HASHES_FILE);
+    fs::create_dir_all(results_dir.as_ref())
+        .and_then(|_| fs::write(&path, serde_json::to_vec_pretty(&hashes)?))
+        .map_err(|e| AlgorithmError::from_io(&path, e))?;
+
+    if let Some(expected) = expected {
+        check_hashes(&hashes.files, &expected)?;
+    }
+
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::sha256_hex;
+    use std::path::PathBuf;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("lib-integrity-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(dir.join("datasets")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn verify_datasets_records_and_checks_hashes() {
+        let dir = test_dir("verify");
+        fs::write(dir.join("datasets/iris.csv"), "a,b\n1,2\n").unwrap();
+        fs::write(dir.join("datasets/.hidden"), "x").unwrap();
+
+        let mut expected = BTreeMap::new();
+        expected.insert(
+            "iris.csv".to_string(),
+            sha256_hex(b"a,b\n1,2\n").to_uppercase(),
+        );
+
+        let hashes = verify_datasets_in(
+            dir.join("datasets"),
+            dir.join("results"),
+            Some(expected.clone()),
+        )
+        .unwrap();
+        assert!(hashes.verified);
+        assert_eq!(hashes.files.len(), 1);
+        assert_eq!(hashes.files[0].size, 8);
+
+        let recorded: DatasetHashes =
+            serde_json::from_slice(&fs::read(dir.join("results/datasets.json")).unwrap()).unwrap();
+        assert_eq!(recorded, hashes);
+
+        let unverified =
+            verify_datasets_in(dir.join("datasets"), dir.join("results"), None).unwrap();
+        assert!(!unverified.verified);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn verify_datasets_rejects_mismatch() {
+        let dir = test_dir("mismatch");
+        fs::write(dir.join("datasets/iris.csv"), "a,b\n1,3\n").unwrap();
+
+        let mut expected = BTreeMap::new();
+        expected.insert("iris.csv".to_string(), sha256_hex(b"a,b\n1,2\n"));
+        let err = verify_datasets_in(
+            dir.join("datasets"),
+            dir.join("results"),
+            Some(expected.clone()),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("iris.csv has SHA-256"));
+        assert!(dir.join("results/datasets.json").exists());
+
+        let files = hash_datasets(dir.join("datasets")).unwrap();
+        expected.insert("iris.csv".to_string(), files[0].sha256.clone());
+        expected.insert("test.csv".to_string(), files[0].sha256.clone());
+        assert!(check_hashes(&files, &expected).is_err());
+        assert!(check_hashes(&files, &BTreeMap::new()).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
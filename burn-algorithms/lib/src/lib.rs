@@ -4,7 +4,51 @@ use burn::train::renderer::{MetricState, MetricsRenderer, TrainingProgress};
 use std::os::unix::net::UnixStream;
 use std::{error::Error, io::Write};
 
+pub mod batch;
+pub mod classification;
+pub mod config;
 #[cfg(not(target_family = "wasm"))]
+pub mod datasets;
+pub mod encryption;
+pub mod error;
+#[cfg(not(target_family = "wasm"))]
+pub mod integrity;
+#[cfg(not(target_family = "wasm"))]
+pub mod metrics;
+pub mod preprocessing;
+pub mod results;
+pub mod scaler;
+pub mod signing;
+pub mod text_model;
+pub mod tokenizer;
+#[cfg(not(target_family = "wasm"))]
+pub mod transport;
+pub mod weights;
+
+pub use batch::{read_batch, read_input, write_predictions, OutputFormat};
+pub use classification::{ClassProbability, Classification};
+pub use config::load_config;
+#[cfg(not(target_family = "wasm"))]
+pub use datasets::{dataset_files, DatasetDescriptor, DatasetFiles, DatasetFormat, DatasetRole};
+pub use error::{exit_with_error, AlgorithmError};
+#[cfg(not(target_family = "wasm"))]
+pub use integrity::{verify_datasets, DatasetHash, DatasetHashes};
+#[cfg(not(target_family = "wasm"))]
+pub use metrics::{
+    cocos_metrics_renderer, CombinedMetricsRenderer, FileMetricsRenderer, MetricEvent,
+    MetricRecord, MetricSplit, SocketMetricsRenderer, TransportMetricsRenderer,
+};
+pub use results::{save_results, write_results_manifest, Manifest, ManifestEntry, ResultsBundle};
+pub use scaler::{FeatureScaler, ScalerKind};
+pub use tokenizer::{Tokenizer, WordPieceTokenizer};
+#[cfg(target_os = "linux")]
+pub use transport::VsockTransport;
+#[cfg(not(target_family = "wasm"))]
+pub use transport::{connect_transport, TcpTransport, Transport, UnixTransport};
+pub use weights::ModelSource;
+
+#[cfg(not(target_family = "wasm"))]
+#[allow(clippy::question_mark)]
 pub fn send_data_via_socket(result: String, path: String) -> Result<(), Box<dyn Error>> {
     let mut stream = match UnixStream::connect(path) {
         Ok(stream) => stream,
@@ -20,6 +64,7 @@ pub fn send_data_via_socket(result: String, path: String) -> Result<(), Box<dyn
     Ok(())
 }
 
+#[allow(clippy::question_mark)]
 pub fn save_results_to_file(result: String, path: String) -> Result<(), Box<dyn Error>> {
     let path = std::path::Path::new(&path);
 
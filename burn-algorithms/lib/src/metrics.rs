@@ -0,0 +1,522 @@
+use crate::{
+    send_data_via_socket,
+    transport::{transport_from_env, Transport, METRICS_TRANSPORT_ENV},
+};
+use burn::train::renderer::{MetricState, MetricsRenderer, TrainingProgress};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, SyncSender},
+    thread,
+};
+
+/// Environment variable holding the Unix socket path metrics are streamed to.
+pub static METRICS_SOCKET_ENV: &str = "METRICS_SOCKET_PATH";
+
+/// Name of the metrics log written into the artifact directory.
+pub static METRICS_FILE: &str = "metrics.jsonl";
+
+/// Number of events a [`TransportMetricsRenderer`] queues while the receiver is slow.
+pub const METRICS_QUEUE_LEN: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricSplit {
+    Train,
+    Valid,
+}
+
+/// A metric update or progress report, serialized as one JSON line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MetricEvent {
+    Metric {
+        split: MetricSplit,
+        name: String,
+        formatted: String,
+        value: Option<f64>,
+    },
+    Progress {
+        split: MetricSplit,
+        epoch: usize,
+        epoch_total: usize,
+        iteration: usize,
+        items_processed: usize,
+        items_total: usize,
+    },
+}
+
+impl MetricEvent {
+    pub fn from_state(split: MetricSplit, state: &MetricState) -> Self {
+        let (entry, value) = match state {
+            MetricState::Generic(entry) => (entry, None),
+            MetricState::Numeric(entry, value) => (entry, Some(*value)),
+        };
+
+        MetricEvent::Metric {
+            split,
+            name: entry.name.clone(),
+            formatted: entry.formatted.clone(),
+            value,
+        }
+    }
+
+    pub fn from_progress(split: MetricSplit, item: &TrainingProgress) -> Self {
+        MetricEvent::Progress {
+            split,
+            epoch: item.epoch,
+            epoch_total: item.epoch_total,
+            iteration: item.iteration,
+            items_processed: item.progress.items_processed,
+            items_total: item.progress.items_total,
+        }
+    }
+
+    pub fn to_json_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Streams metric updates and training progress as JSON lines to a Unix socket.
+///
+/// Without a socket path nothing is sent, which makes it a drop-in replacement
+/// for [`crate::EmptyMetricsRenderer`]. Send failures are ignored so that an
+/// unavailable receiver never interrupts training.
+pub struct SocketMetricsRenderer {
+    path: Option<String>,
+}
+
+impl SocketMetricsRenderer {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Reads the socket path from the `METRICS_SOCKET_PATH` environment variable.
+    pub fn from_env() -> Self {
+        Self {
+            path: std::env::var(METRICS_SOCKET_ENV).ok(),
+        }
+    }
+
+    fn send(&self, event: MetricEvent) {
+        if let Some(path) = &self.path {
+            send_data_via_socket(event.to_json_line(), path.clone()).ok();
+        }
+    }
+}
+
+impl MetricsRenderer for SocketMetricsRenderer {
+    fn update_train(&mut self, state: MetricState) {
+        self.send(MetricEvent::from_state(MetricSplit::Train, &state));
+    }
+
+    fn update_valid(&mut self, state: MetricState) {
+        self.send(MetricEvent::from_state(MetricSplit::Valid, &state));
+    }
+
+    fn render_train(&mut self, item: TrainingProgress) {
+        self.send(MetricEvent::from_progress(MetricSplit::Train, &item));
+    }
+
+    fn render_valid(&mut self, item: TrainingProgress) {
+        self.send(MetricEvent::from_progress(MetricSplit::Valid, &item));
+    }
+}
+
+/// Sends every metric update and progress report as one JSON frame over a [`Transport`].
+///
+/// Frames are sent by a background thread, so that training never waits for the
+/// receiver to acknowledge them, and events are dropped while [`METRICS_QUEUE_LEN`]
+/// of them are waiting. Like [`SocketMetricsRenderer`], failed sends are ignored and
+/// retried on a new connection with the next event.
+pub struct TransportMetricsRenderer {
+    queue: SyncSender<Vec<u8>>,
+}
+
+impl TransportMetricsRenderer {
+    pub fn new(mut transport: Box<dyn Transport>) -> Self {
+        let (queue, frames) = mpsc::sync_channel::<Vec<u8>>(METRICS_QUEUE_LEN);
+        thread::spawn(move || {
+            for data in frames {
+                transport.send(&data).ok();
+            }
+        });
+
+        Self { queue }
+    }
+
+    fn send(&mut self, event: MetricEvent) {
+        if let Ok(data) = serde_json::to_vec(&event) {
+            self.queue.try_send(data).ok();
+        }
+    }
+}
+
+impl MetricsRenderer for TransportMetricsRenderer {
+    fn update_train(&mut self, state: MetricState) {
+        self.send(MetricEvent::from_state(MetricSplit::Train, &state));
+    }
+
+    fn update_valid(&mut self, state: MetricState) {
+        self.send(MetricEvent::from_state(MetricSplit::Valid, &state));
+    }
+
+    fn render_train(&mut self, item: TrainingProgress) {
+        self.send(MetricEvent::from_progress(MetricSplit::Train, &item));
+    }
+
+    fn render_valid(&mut self, item: TrainingProgress) {
+        self.send(MetricEvent::from_progress(MetricSplit::Valid, &item));
+    }
+}
+
+/// One metric value at a given epoch and iteration, as stored in `metrics.jsonl`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetricRecord {
+    pub epoch: usize,
+    pub iteration: usize,
+    pub split: MetricSplit,
+    pub name: String,
+    pub value: Option<f64>,
+}
+
+/// Appends every train and valid metric update to `metrics.jsonl` in the artifact directory.
+///
+/// Burn reports the metric states of an iteration before its progress, so the
+/// states are held until the matching `render_*` call provides the epoch and iteration.
+pub struct FileMetricsRenderer {
+    path: PathBuf,
+    file: Option<File>,
+    pending: Vec<(MetricSplit, String, Option<f64>)>,
+}
+
+impl FileMetricsRenderer {
+    pub fn new(artifact_dir: impl AsRef<Path>) -> Self {
+        Self {
+            path: artifact_dir.as_ref().join(METRICS_FILE),
+            file: None,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn update(&mut self, split: MetricSplit, state: MetricState) {
+        let (entry, value) = match state {
+            MetricState::Generic(entry) => (entry, None),
+            MetricState::Numeric(entry, value) => (entry, Some(value)),
+        };
+        self.pending.push((split, entry.name, value));
+    }
+
+    fn flush(&mut self, split: MetricSplit, item: &TrainingProgress) {
+        let (records, pending): (Vec<_>, Vec<_>) =
+            self.pending.drain(..).partition(|(s, _, _)| *s == split);
+        self.pending = pending;
+
+        let lines: String = records
+            .into_iter()
+            .map(|(split, name, value)| MetricRecord {
+                epoch: item.epoch,
+                iteration: item.iteration,
+                split,
+                name,
+                value,
+            })
+            .filter_map(|record| serde_json::to_string(&record).ok())
+            .map(|line| line + "\n")
+            .collect();
+
+        if !lines.is_empty() {
+            if let Err(e) = self.append(lines.as_bytes()) {
+                eprintln!("Failed to write {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    fn append(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.file = Some(file);
+        }
+
+        match self.file.as_mut() {
+            Some(file) => file.write_all(data),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MetricsRenderer for FileMetricsRenderer {
+    fn update_train(&mut self, state: MetricState) {
+        self.update(MetricSplit::Train, state);
+    }
+
+    fn update_valid(&mut self, state: MetricState) {
+        self.update(MetricSplit::Valid, state);
+    }
+
+    fn render_train(&mut self, item: TrainingProgress) {
+        self.flush(MetricSplit::Train, &item);
+    }
+
+    fn render_valid(&mut self, item: TrainingProgress) {
+        self.flush(MetricSplit::Valid, &item);
+    }
+}
+
+/// Forwards every update to each of the wrapped renderers.
+pub struct CombinedMetricsRenderer {
+    renderers: Vec<Box<dyn MetricsRenderer>>,
+}
+
+impl CombinedMetricsRenderer {
+    pub fn new(renderers: Vec<Box<dyn MetricsRenderer>>) -> Self {
+        Self { renderers }
+    }
+}
+
+impl MetricsRenderer for CombinedMetricsRenderer {
+    fn update_train(&mut self, state: MetricState) {
+        for renderer in self.renderers.iter_mut() {
+            renderer.update_train(clone_state(&state));
+        }
+    }
+
+    fn update_valid(&mut self, state: MetricState) {
+        for renderer in self.renderers.iter_mut() {
+            renderer.update_valid(clone_state(&state));
+        }
+    }
+
+    fn render_train(&mut self, item: TrainingProgress) {
+        for renderer in self.renderers.iter_mut() {
+            renderer.render_train(clone_progress(&item));
+        }
+    }
+
+    fn render_valid(&mut self, item: TrainingProgress) {
+        for renderer in self.renderers.iter_mut() {
+            renderer.render_valid(clone_progress(&item));
+        }
+    }
+
+    fn on_train_end(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        for renderer in self.renderers.iter_mut() {
+            renderer.on_train_end()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renderer used by training binaries in cocos mode: metrics are streamed to the
+/// socket from `METRICS_SOCKET_PATH`, sent over the transport from `METRICS_TRANSPORT`
+/// and logged to `metrics.jsonl` in `artifact_dir`.
+pub fn cocos_metrics_renderer(artifact_dir: impl AsRef<Path>) -> CombinedMetricsRenderer {
+    let mut renderers: Vec<Box<dyn MetricsRenderer>> = vec![
+        Box::new(SocketMetricsRenderer::from_env()),
+        Box::new(FileMetricsRenderer::new(artifact_dir)),
+    ];
+
+    match transport_from_env(METRICS_TRANSPORT_ENV) {
+        Ok(Some(transport)) => renderers.push(Box::new(TransportMetricsRenderer::new(transport))),
+        Ok(None) => (),
+        Err(e) => eprintln!("Failed to set up metrics transport: {}", e),
+    }
+
+    CombinedMetricsRenderer::new(renderers)
+}
+
+fn clone_state(state: &MetricState) -> MetricState {
+    match state {
+        MetricState::Generic(entry) => MetricState::Generic(entry.clone()),
+        MetricState::Numeric(entry, value) => MetricState::Numeric(entry.clone(), *value),
+    }
+}
+
+fn clone_progress(item: &TrainingProgress) -> TrainingProgress {
+    let mut progress = TrainingProgress::none();
+    progress.progress.items_processed = item.progress.items_processed;
+    progress.progress.items_total = item.progress.items_total;
+    progress.epoch = item.epoch;
+    progress.epoch_total = item.epoch_total;
+    progress.iteration = item.iteration;
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::train::metric::MetricEntry;
+    use std::{io::Read, os::unix::net::UnixListener};
+
+    #[test]
+    fn socket_metrics_renderer_sends_json_lines() {
+        let path = std::env::temp_dir().join(format!("lib-metrics-{}.sock", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let receiver = thread::spawn(move || {
+            let mut events = Vec::new();
+            for stream in listener.incoming().take(2) {
+                let mut line = String::new();
+                stream.unwrap().read_to_string(&mut line).unwrap();
+                events.push(line);
+            }
+            events
+        });
+
+        let mut renderer = SocketMetricsRenderer::new(path.to_string_lossy());
+        renderer.update_train(MetricState::Numeric(
+            MetricEntry::new("Loss".to_string(), "0.500".to_string(), "0.5".to_string()),
+            0.5,
+        ));
+        let mut progress = TrainingProgress::none();
+        progress.epoch = 1;
+        progress.epoch_total = 10;
+        progress.iteration = 3;
+        renderer.render_valid(progress);
+
+        let events = receiver.join().unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(events.iter().all(|line| line.ends_with('\n')));
+        let events: Vec<MetricEvent> = events
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            events[0],
+            MetricEvent::Metric {
+                split: MetricSplit::Train,
+                name: "Loss".to_string(),
+                formatted: "0.500".to_string(),
+                value: Some(0.5),
+            }
+        );
+        assert_eq!(
+            events[1],
+            MetricEvent::Progress {
+                split: MetricSplit::Valid,
+                epoch: 1,
+                epoch_total: 10,
+                iteration: 3,
+                items_processed: 0,
+                items_total: 0,
+            }
+        );
+    }
+
+    /// Forwards frames once the test lets it, like a receiver that is slow to acknowledge.
+    struct BlockedTransport {
+        released: mpsc::Receiver<()>,
+        frames: mpsc::Sender<Vec<u8>>,
+    }
+
+    impl Transport for BlockedTransport {
+        fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+            self.released.recv()?;
+            self.frames.send(data.to_vec())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn transport_metrics_renderer_does_not_wait_for_the_receiver() {
+        let (release, released) = mpsc::channel();
+        let (frames, received) = mpsc::channel();
+        let mut renderer =
+            TransportMetricsRenderer::new(Box::new(BlockedTransport { released, frames }));
+
+        for iteration in 1..=3 {
+            let mut progress = TrainingProgress::none();
+            progress.iteration = iteration;
+            renderer.render_train(progress);
+        }
+        drop(renderer);
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+
+        let iterations = received
+            .iter()
+            .map(|frame| match serde_json::from_slice(&frame).unwrap() {
+                MetricEvent::Progress { iteration, .. } => iteration,
+                event => panic!("Unexpected event {:?}", event),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(iterations, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn socket_metrics_renderer_without_path_is_silent() {
+        let mut renderer = SocketMetricsRenderer { path: None };
+        renderer.render_train(TrainingProgress::none());
+    }
+
+    #[test]
+    fn file_metrics_renderer_appends_records() {
+        let dir = std::env::temp_dir().join(format!("lib-metrics-file-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+
+        let loss = |value: f64| {
+            MetricState::Numeric(
+                MetricEntry::new("Loss".to_string(), value.to_string(), value.to_string()),
+                value,
+            )
+        };
+        let progress = |epoch: usize, iteration: usize| {
+            let mut progress = TrainingProgress::none();
+            progress.epoch = epoch;
+            progress.iteration = iteration;
+            progress
+        };
+
+        let mut renderer = FileMetricsRenderer::new(&dir);
+        renderer.update_train(loss(0.9));
+        renderer.update_valid(loss(0.7));
+        renderer.render_train(progress(1, 1));
+        renderer.render_valid(progress(1, 2));
+        renderer.render_train(progress(1, 3));
+        renderer.update_train(loss(0.5));
+        renderer.render_train(progress(2, 1));
+
+        let records: Vec<MetricRecord> = fs::read_to_string(renderer.path())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        fs::remove_dir_all(&dir).ok();
+
+        let expected = |epoch, iteration, split, value| MetricRecord {
+            epoch,
+            iteration,
+            split,
+            name: "Loss".to_string(),
+            value: Some(value),
+        };
+        assert_eq!(
+            records,
+            vec![
+                expected(1, 1, MetricSplit::Train, 0.9),
+                expected(1, 2, MetricSplit::Valid, 0.7),
+                expected(2, 1, MetricSplit::Train, 0.5),
+            ]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use burn::prelude::*;
+
+/// Width and height of an MNIST image, in pixels.
+pub const MNIST_IMAGE_SIZE: usize = 28;
+
+/// Mean and standard deviation of the MNIST training pixels once scaled to `[0, 1]`,
+/// copied from the PyTorch MNIST example:
+/// <https://github.com/pytorch/examples/blob/54f4572509891883a947411fd7239237dd2a39c3/mnist/main.py#L122>
+pub const MNIST_MEAN: f32 = 0.1307;
+pub const MNIST_STD: f32 = 0.3081;
+
+/// Normalizes raw MNIST pixels, between 0 and 255, to a mean of 0 and a standard deviation
+/// of 1. Training and inference both go through it, so the model always sees the inputs
+/// it was trained on.
+pub fn normalize_mnist<B: Backend, const D: usize>(pixels: Tensor<B, D>) -> Tensor<B, D> {
+    ((pixels / 255) - MNIST_MEAN) / MNIST_STD
+}
+
+/// Width and height of a CIFAR-10 image, in pixels.
+pub const CIFAR10_IMAGE_SIZE: usize = 32;
+
+/// Per channel mean and standard deviation of the CIFAR-10 training pixels once scaled
+/// to `[0, 1]`, in RGB order.
+pub const CIFAR10_MEAN: [f32; 3] = [0.4914, 0.48216, 0.44653];
+pub const CIFAR10_STD: [f32; 3] = [0.24703, 0.24349, 0.26159];
+
+/// Normalizes CIFAR-10 images of shape `[batch_size, 3, height, width]`, with pixels
+/// between 0 and 1, with the per channel [`CIFAR10_MEAN`] and [`CIFAR10_STD`].
+pub fn normalize_cifar10<B: Backend>(images: Tensor<B, 4>) -> Tensor<B, 4> {
+    let device = images.device();
+    let mean = Tensor::<B, 1>::from_floats(CIFAR10_MEAN, &device).reshape([1, 3, 1, 1]);
+    let std = Tensor::<B, 1>::from_floats(CIFAR10_STD, &device).reshape([1, 3, 1, 1]);
+
+    (images - mean) / std
+}
+
+/// Names of the Wine Quality input features, the columns of the dataset CSV files, in the
+/// order the model is trained on.
+pub const WINE_QUALITY_FEATURES: [&str; 11] = [
+    "fixed acidity",
+    "volatile acidity",
+    "citric acid",
+    "residual sugar",
+    "chlorides",
+    "free sulfur dioxide",
+    "total sulfur dioxide",
+    "density",
+    "pH",
+    "sulphates",
+    "alcohol",
+];
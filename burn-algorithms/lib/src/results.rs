@@ -0,0 +1,436 @@
+use crate::{
+    encryption::{self, SEALED_EXTENSION, SEALED_MEDIA_TYPE},
+    signing::{self, SIGNATURE_FILE, SIGNING_PUBLIC_KEY_FILE},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use x25519_dalek::PublicKey;
+
+/// Directory cocos collects results from once the algorithm exits.
+pub static RESULTS_DIR: &str = "results";
+
+/// Default artifact name used by binaries that produce a single result.
+pub static RESULTS_FILE: &str = "results.txt";
+
+/// Name of the manifest written next to the artifacts of a bundle.
+pub static MANIFEST_FILE: &str = "manifest.json";
+
+/// Directory cocos places uploaded datasets in.
+pub static DATASETS_DIR: &str = "datasets";
+
+/// A single artifact entry in the results manifest.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub size: u64,
+    pub media_type: String,
+    pub sha256: String,
+}
+
+/// Describes every artifact written to the results directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Reads a manifest previously written by [`ResultsBundle::write_manifest`].
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let data = fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Checks that every listed artifact in `dir` still has the recorded size and digest.
+    pub fn verify(&self, dir: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        for entry in self.files.iter() {
+            let data = fs::read(dir.as_ref().join(&entry.name))?;
+            if data.len() as u64 != entry.size || sha256_hex(&data) != entry.sha256 {
+                return Err(format!("Artifact {} does not match the manifest", entry.name).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects named artifacts in a results directory and describes them in a `manifest.json`.
+///
+/// Artifacts are written as they are added, the manifest is only written by
+/// [`ResultsBundle::write_manifest`]. With a recipient key, every artifact is sealed
+/// to it and stored as `<name>.sealed` instead, while the manifest stays readable.
+pub struct ResultsBundle {
+    dir: PathBuf,
+    manifest: Manifest,
+    recipient: Option<PublicKey>,
+}
+
+impl Default for ResultsBundle {
+    fn default() -> Self {
+        Self::new(RESULTS_DIR)
+    }
+}
+
+impl ResultsBundle {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            manifest: Manifest::default(),
+            recipient: None,
+        }
+    }
+
+    /// Creates a bundle that seals its artifacts to the data consumer key, if one is
+    /// configured through the environment or the datasets directory.
+    pub fn from_env(dir: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let bundle = Self::new(dir);
+        Ok(match encryption::recipient_key(DATASETS_DIR)? {
+            Some(recipient) => bundle.with_recipient(recipient),
+            None => bundle,
+        })
+    }
+
+    pub fn with_recipient(mut self, recipient: PublicKey) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Writes `data` to `name` inside the bundle directory and records it.
+    pub fn add_bytes(
+        &mut self,
+        name: &str,
+        media_type: &str,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        match self.recipient {
+            Some(recipient) => {
+                let sealed = encryption::seal(&recipient, data)?;
+                self.write(&sealed_name(name), SEALED_MEDIA_TYPE, &sealed)
+            }
+            None => self.write(name, media_type, data),
+        }
+    }
+
+    pub fn add_text(&mut self, name: &str, text: &str) -> Result<(), Box<dyn Error>> {
+        self.add_bytes(name, "text/plain", text.as_bytes())
+    }
+
+    pub fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), Box<dyn Error>> {
+        let data = serde_json::to_vec_pretty(value)?;
+        self.add_bytes(name, "application/json", &data)
+    }
+
+    /// Records a file that was already written to the bundle directory, for example
+    /// a model record saved by a burn recorder. When sealing, the plaintext file is
+    /// replaced by its sealed copy.
+    pub fn add_file(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let path = self.dir.join(name);
+        let data = fs::read(&path)?;
+
+        if self.recipient.is_none() || is_sealed(name) {
+            let media_type = match is_sealed(name) {
+                true => SEALED_MEDIA_TYPE,
+                false => media_type(Path::new(name)),
+            };
+            self.record(name, media_type, &data);
+            return Ok(());
+        }
+
+        self.add_bytes(name, media_type(Path::new(name)), &data)?;
+        fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    /// Records every file in the bundle directory that is not already part of the bundle.
+    pub fn add_existing_files(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut names = Vec::new();
+        collect_files(&self.dir, &self.dir, &mut names)?;
+        names.sort();
+
+        for name in names {
+            if [MANIFEST_FILE, SIGNATURE_FILE, SIGNING_PUBLIC_KEY_FILE].contains(&name.as_str())
+                || self.manifest.files.iter().any(|f| f.name == name)
+            {
+                continue;
+            }
+            self.add_file(&name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `manifest.json` into the bundle directory and returns its path.
+    pub fn write_manifest(&self) -> Result<PathBuf, Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+
+        let path = self.dir.join(MANIFEST_FILE);
+        let data = serde_json::to_vec_pretty(&self.manifest)?;
+        fs::write(&path, data)?;
+
+        Ok(path)
+    }
+
+    /// Signs the written manifest with the workload key, see [`signing::sign_manifest`].
+    pub fn sign(&self, key: &ed25519_dalek::SigningKey) -> Result<PathBuf, Box<dyn Error>> {
+        signing::sign_manifest(&self.dir, key)
+    }
+
+    /// Signs the written manifest with the key from `RESULTS_SIGNING_KEY` and returns the
+    /// signature path. When it is unset the results are left unsigned, and the signature of
+    /// an earlier run is removed so that it is not taken for the signature of these results.
+    pub fn sign_from_env(&self) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        if let Some(key) = signing::signing_key()? {
+            return Ok(Some(self.sign(&key)?));
+        }
+
+        eprintln!(
+            "{} is unset, the results are not signed",
+            signing::RESULTS_SIGNING_KEY_ENV
+        );
+        for name in [SIGNATURE_FILE, SIGNING_PUBLIC_KEY_FILE] {
+            match fs::remove_file(self.dir.join(name)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn write(&mut self, name: &str, media_type: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let path = self.dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut output = fs::File::create(&path)?;
+        output.write_all(data)?;
+
+        self.record(name, media_type, data);
+
+        Ok(())
+    }
+
+    fn record(&mut self, name: &str, media_type: &str, data: &[u8]) {
+        let entry = ManifestEntry {
+            name: name.to_string(),
+            size: data.len() as u64,
+            media_type: media_type.to_string(),
+            sha256: sha256_hex(data),
+        };
+
+        match self.manifest.files.iter_mut().find(|f| f.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.manifest.files.push(entry),
+        }
+    }
+}
+
+/// Writes a single result to `results/results.txt` together with its manifest, signed when
+/// `RESULTS_SIGNING_KEY` is set.
+pub fn save_results(result: String) -> Result<(), Box<dyn Error>> {
+    let mut bundle = ResultsBundle::from_env(RESULTS_DIR)?;
+    bundle.add_text(RESULTS_FILE, &result)?;
+    bundle.write_manifest()?;
+    bundle.sign_from_env()?;
+
+    #[cfg(not(target_family = "wasm"))]
+    deliver(&bundle)?;
+
+    Ok(())
+}
+
+/// Records every file already written to `dir` and writes its manifest, signed when
+/// `RESULTS_SIGNING_KEY` is set.
+///
+/// Training binaries call this once the model, config and learner logs are saved.
+pub fn write_results_manifest(dir: impl AsRef<Path>) -> Result<PathBuf, Box<dyn Error>> {
+    let mut bundle = ResultsBundle::from_env(dir)?;
+    bundle.add_existing_files()?;
+    let path = bundle.write_manifest()?;
+    bundle.sign_from_env()?;
+
+    #[cfg(not(target_family = "wasm"))]
+    deliver(&bundle)?;
+
+    Ok(path)
+}
+
+/// Sends the bundle over the transport from `RESULTS_TRANSPORT`, if one is configured.
+#[cfg(not(target_family = "wasm"))]
+fn deliver(bundle: &ResultsBundle) -> Result<(), Box<dyn Error>> {
+    use crate::transport::{send_bundle, transport_from_env, RESULTS_TRANSPORT_ENV};
+
+    if let Some(mut transport) = transport_from_env(RESULTS_TRANSPORT_ENV)? {
+        send_bundle(transport.as_mut(), bundle)?;
+    }
+
+    Ok(())
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn sealed_name(name: &str) -> String {
+    format!("{}.{}", name, SEALED_EXTENSION)
+}
+
+fn is_sealed(name: &str) -> bool {
+    Path::new(name).extension().and_then(|e| e.to_str()) == Some(SEALED_EXTENSION)
+}
+
+fn media_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => "application/json",
+        Some("jsonl") => "application/x-ndjson",
+        Some("txt") | Some("log") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, names: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, names)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            names.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lib-results-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn results_bundle_writes_manifest() {
+        let dir = test_dir("manifest");
+        let mut bundle = ResultsBundle::new(&dir);
+        bundle.add_text("results.txt", "test").unwrap();
+        bundle
+            .add_json("metrics.json", &serde_json::json!({"accuracy": 1.0}))
+            .unwrap();
+        let path = bundle.write_manifest().unwrap();
+
+        let manifest = Manifest::read(path).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files[0].name, "results.txt");
+        assert_eq!(manifest.files[0].size, 4);
+        assert_eq!(manifest.files[0].media_type, "text/plain");
+        assert_eq!(
+            manifest.files[0].sha256,
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+        assert_eq!(manifest.files[1].media_type, "application/json");
+        assert!(manifest.verify(&dir).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn results_bundle_records_existing_files() {
+        let dir = test_dir("existing");
+        fs::create_dir_all(dir.join("checkpoint")).unwrap();
+        fs::write(dir.join("model.bin"), [1, 2, 3]).unwrap();
+        fs::write(dir.join("checkpoint/model-1.mpk"), [4]).unwrap();
+
+        let mut bundle = ResultsBundle::new(&dir);
+        bundle.add_text("results.txt", "test").unwrap();
+        bundle.add_existing_files().unwrap();
+        bundle.write_manifest().unwrap();
+        bundle.add_existing_files().unwrap();
+
+        let names: Vec<&str> = bundle
+            .manifest()
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["results.txt", "checkpoint/model-1.mpk", "model.bin"]
+        );
+        assert_eq!(
+            bundle.manifest().files[2].media_type,
+            "application/octet-stream"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn results_bundle_seals_artifacts() {
+        let dir = test_dir("sealed");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("model.bin"), [1, 2, 3]).unwrap();
+
+        let (secret, public) = encryption::generate_keypair();
+        let mut bundle = ResultsBundle::new(&dir).with_recipient(public);
+        bundle.add_text("results.txt", "test").unwrap();
+        bundle.add_existing_files().unwrap();
+        bundle.write_manifest().unwrap();
+
+        let names: Vec<&str> = bundle
+            .manifest()
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["results.txt.sealed", "model.bin.sealed"]);
+        assert!(!dir.join("results.txt").exists());
+        assert!(!dir.join("model.bin").exists());
+        assert!(bundle.manifest().verify(&dir).is_ok());
+
+        let sealed = fs::read(dir.join("model.bin.sealed")).unwrap();
+        assert_eq!(encryption::open(&secret, &sealed).unwrap(), vec![1, 2, 3]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn manifest_verify_detects_tampering() {
+        let dir = test_dir("tamper");
+        let mut bundle = ResultsBundle::new(&dir);
+        bundle.add_text("results.txt", "test").unwrap();
+        bundle.write_manifest().unwrap();
+
+        fs::write(dir.join("results.txt"), "tampered").unwrap();
+        assert!(bundle.manifest().verify(&dir).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::AlgorithmError;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// File in the artifact directory holding the feature scaler of a model.
+pub static SCALER_FILE: &str = "scaler.json";
+
+/// How a [`FeatureScaler`] normalizes each feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalerKind {
+    /// Scales to `[0, 1]` with the minimum and maximum of the training data.
+    #[default]
+    MinMax,
+    /// Centers on the mean and divides by the standard deviation of the training data.
+    ZScore,
+}
+
+/// Per feature normalization fitted on the training split.
+///
+/// It is saved next to the model, so that inference normalizes its inputs exactly like
+/// the data the model was trained on. A feature is mapped to `(value - offset) / scale`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeatureScaler {
+    pub kind: ScalerKind,
+    pub offset: Vec<f64>,
+    pub scale: Vec<f64>,
+}
+
+impl FeatureScaler {
+    /// Computes the scaler of `kind` from the feature rows of the training data.
+    pub fn fit<R: AsRef<[f64]>>(
+        kind: ScalerKind,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, AlgorithmError> {
+        let rows = rows.into_iter().collect::<Vec<_>>();
+        let len = match rows.first() {
+            Some(row) => row.as_ref().len(),
+            None => {
+                return Err(AlgorithmError::InvalidDataset(
+                    "Cannot fit a scaler on an empty dataset".to_string(),
+                ))
+            }
+        };
+        if let Some(row) = rows.iter().find(|row| row.as_ref().len() != len) {
+            return Err(AlgorithmError::InvalidDataset(format!(
+                "Rows have {} and {} features",
+                len,
+                row.as_ref().len()
+            )));
+        }
+
+        let column = |i: usize| rows.iter().map(move |row| row.as_ref()[i]);
+        let (offset, scale) = (0..len)
+            .map(|i| match kind {
+                ScalerKind::MinMax => {
+                    let min = column(i).fold(f64::INFINITY, f64::min);
+                    let max = column(i).fold(f64::NEG_INFINITY, f64::max);
+                    (min, max - min)
+                }
+                ScalerKind::ZScore => {
+                    let count = rows.len() as f64;
+                    let mean = column(i).sum::<f64>() / count;
+                    let variance = column(i).map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+                    (mean, variance.sqrt())
+                }
+            })
+            .unzip();
+
+        Ok(Self {
+            kind,
+            offset,
+            scale,
+        })
+    }
+
+    /// Number of features the scaler was fitted on.
+    pub fn len(&self) -> usize {
+        self.offset.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset.is_empty()
+    }
+
+    /// Normalizes one row of features. A feature that was constant in the training data
+    /// is mapped to 0.
+    pub fn transform(&self, features: &[f64]) -> Vec<f32> {
+        features
+            .iter()
+            .zip(self.offset.iter().zip(&self.scale))
+            .map(|(value, (offset, scale))| match *scale {
+                scale if scale > 0.0 => ((value - offset) / scale) as f32,
+                _ => 0.0,
+            })
+            .collect()
+    }
+
+    pub fn from_slice(data: &[u8]) -> Result<Self, AlgorithmError> {
+        serde_json::from_slice(data)
+            .map_err(|e| AlgorithmError::InvalidConfig(format!("Invalid feature scaler: {}", e)))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AlgorithmError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(AlgorithmError::MissingFile(path.to_path_buf()));
+        }
+        let data = fs::read(path).map_err(|source| AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        Self::from_slice(&data)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AlgorithmError> {
+        let path = path.as_ref();
+        let data = serde_json::to_vec_pretty(self).expect("Failed to serialize feature scaler");
+
+        fs::write(path, data).map_err(|source| AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: [[f64; 2]; 3] = [[1.0, 5.0], [3.0, 5.0], [2.0, 5.0]];
+
+    #[test]
+    fn min_max_scaler_maps_training_range_to_unit_interval() {
+        let scaler = FeatureScaler::fit(ScalerKind::MinMax, ROWS).unwrap();
+
+        assert_eq!(scaler.offset, vec![1.0, 5.0]);
+        assert_eq!(scaler.scale, vec![2.0, 0.0]);
+        assert_eq!(scaler.transform(&[1.0, 5.0]), vec![0.0, 0.0]);
+        assert_eq!(scaler.transform(&[3.0, 7.0]), vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn z_score_scaler_centers_and_reduces() {
+        let scaler = FeatureScaler::fit(ScalerKind::ZScore, ROWS).unwrap();
+
+        assert_eq!(scaler.offset, vec![2.0, 5.0]);
+        assert!((scaler.scale[0] - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(scaler.transform(&[2.0, 5.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn scaler_rejects_empty_and_ragged_rows() {
+        let empty: [[f64; 2]; 0] = [];
+        assert!(FeatureScaler::fit(ScalerKind::MinMax, empty).is_err());
+        assert!(FeatureScaler::fit(ScalerKind::MinMax, [vec![1.0], vec![1.0, 2.0]]).is_err());
+    }
+
+    #[test]
+    fn scaler_round_trips_through_file() {
+        let dir = std::env::temp_dir().join(format!("lib-scaler-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SCALER_FILE);
+        let scaler = FeatureScaler::fit(ScalerKind::ZScore, ROWS).unwrap();
+
+        scaler.save(&path).unwrap();
+        assert_eq!(FeatureScaler::load(&path).unwrap(), scaler);
+        assert!(matches!(
+            FeatureScaler::load(dir.join("missing.json")),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+        assert!(FeatureScaler::from_slice(b"{}").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
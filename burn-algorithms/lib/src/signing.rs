@@ -0,0 +1,179 @@
+use crate::{
+    error::AlgorithmError,
+    results::{sha256_hex, Manifest, MANIFEST_FILE},
+};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable holding the hex encoded Ed25519 seed of the workload signing key.
+pub static RESULTS_SIGNING_KEY_ENV: &str = "RESULTS_SIGNING_KEY";
+
+/// Environment variable holding the hex encoded public key a reader trusts for results.
+pub static RESULTS_VERIFYING_KEY_ENV: &str = "RESULTS_VERIFYING_KEY";
+
+/// Detached signature of `manifest.json`, written next to it.
+pub static SIGNATURE_FILE: &str = "manifest.json.sig";
+
+/// Public half of the key that signed the manifest, written next to it.
+pub static SIGNING_PUBLIC_KEY_FILE: &str = "signing.pub";
+
+/// Returns the workload signing key from `RESULTS_SIGNING_KEY`, or `None` when it is unset
+/// and the results are not to be signed. A key that is not a hex encoded 32 byte seed is an
+/// invalid config.
+pub fn signing_key() -> Result<Option<SigningKey>, AlgorithmError> {
+    match std::env::var(RESULTS_SIGNING_KEY_ENV) {
+        Ok(value) => key_bytes(&value)
+            .map(|seed| Some(SigningKey::from_bytes(&seed)))
+            .map_err(|e| {
+                AlgorithmError::InvalidConfig(format!(
+                    "{} is not a hex encoded 32 byte seed: {}",
+                    RESULTS_SIGNING_KEY_ENV, e
+                ))
+            }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Signs the `manifest.json` in `dir`, writing the hex encoded detached signature to
+/// `manifest.json.sig` and the public key to `signing.pub`. Returns the signature path.
+pub fn sign_manifest(dir: impl AsRef<Path>, key: &SigningKey) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = dir.as_ref();
+    let manifest = fs::read(dir.join(MANIFEST_FILE))?;
+    let signature = key.sign(&manifest);
+
+    let path = dir.join(SIGNATURE_FILE);
+    fs::write(&path, hex::encode(signature.to_bytes()))?;
+    fs::write(
+        dir.join(SIGNING_PUBLIC_KEY_FILE),
+        hex::encode(key.verifying_key().as_bytes()),
+    )?;
+
+    Ok(path)
+}
+
+/// Checks the detached signature of the `manifest.json` in `dir` and returns the manifest.
+pub fn verify_manifest(
+    dir: impl AsRef<Path>,
+    key: &VerifyingKey,
+) -> Result<Manifest, Box<dyn Error>> {
+    let dir = dir.as_ref();
+    let manifest = fs::read(dir.join(MANIFEST_FILE))?;
+    let signature = fs::read_to_string(dir.join(SIGNATURE_FILE))?;
+    let signature = Signature::from_slice(&hex::decode(signature.trim())?)?;
+
+    key.verify(&manifest, &signature)
+        .map_err(|_| "Manifest signature is invalid")?;
+
+    Ok(serde_json::from_slice(&manifest)?)
+}
+
+/// Reads the artifact at `path` after checking the signed manifest next to it and the
+/// artifact digest recorded in that manifest.
+pub fn read_verified(
+    path: impl AsRef<Path>,
+    key: &VerifyingKey,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid artifact path {}", path.display()))?;
+
+    let manifest = verify_manifest(dir, key)?;
+    let entry = manifest
+        .files
+        .iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| format!("Artifact {} is not listed in the manifest", name))?;
+
+    let data = fs::read(path)?;
+    if data.len() as u64 != entry.size || sha256_hex(&data) != entry.sha256 {
+        return Err(format!("Artifact {} does not match the manifest", name).into());
+    }
+
+    Ok(data)
+}
+
+/// Returns the key a reader trusts from `RESULTS_VERIFYING_KEY`, which must hold the
+/// pinned or attested public key of the workload. The `signing.pub` written next to the
+/// manifest is not a trust anchor: whoever can change the results can replace it too.
+pub fn verifying_key() -> Result<VerifyingKey, Box<dyn Error>> {
+    let value = std::env::var(RESULTS_VERIFYING_KEY_ENV).map_err(|_| {
+        format!(
+            "{} must hold the pinned or attested public key of the workload",
+            RESULTS_VERIFYING_KEY_ENV
+        )
+    })?;
+
+    verifying_key_from_hex(&value)
+}
+
+/// Parses a hex encoded Ed25519 public key.
+pub fn verifying_key_from_hex(value: &str) -> Result<VerifyingKey, Box<dyn Error>> {
+    Ok(VerifyingKey::from_bytes(&key_bytes(value)?)?)
+}
+
+fn key_bytes(value: &str) -> Result<[u8; 32], Box<dyn Error>> {
+    let bytes = hex::decode(value.trim())?;
+    bytes
+        .try_into()
+        .map_err(|_| "Key must be 32 bytes long".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::ResultsBundle;
+    use rand_core::OsRng;
+
+    #[test]
+    fn signed_manifest_verifies_artifacts() {
+        let dir = std::env::temp_dir().join(format!("lib-signing-{}", std::process::id()));
+        let mut bundle = ResultsBundle::new(&dir);
+        bundle.add_text("results.txt", "42").unwrap();
+        bundle.write_manifest().unwrap();
+
+        let key = SigningKey::generate(&mut OsRng);
+        sign_manifest(&dir, &key).unwrap();
+        let trusted = verifying_key_from_hex(&hex::encode(key.verifying_key().as_bytes())).unwrap();
+        assert_eq!(trusted, key.verifying_key());
+        assert_eq!(
+            read_verified(dir.join("results.txt"), &trusted).unwrap(),
+            b"42"
+        );
+
+        let other = SigningKey::generate(&mut OsRng).verifying_key();
+        assert!(read_verified(dir.join("results.txt"), &other).is_err());
+
+        fs::write(dir.join("results.txt"), "43").unwrap();
+        assert!(read_verified(dir.join("results.txt"), &trusted).is_err());
+
+        // Tampered results re-signed with another key, which replaces signing.pub
+        let mut forged = ResultsBundle::new(&dir);
+        forged.add_text("results.txt", "43").unwrap();
+        forged.write_manifest().unwrap();
+        sign_manifest(&dir, &SigningKey::generate(&mut OsRng)).unwrap();
+        let published =
+            verifying_key_from_hex(&fs::read_to_string(dir.join(SIGNING_PUBLIC_KEY_FILE)).unwrap())
+                .unwrap();
+        assert!(read_verified(dir.join("results.txt"), &published).is_ok());
+        assert!(read_verified(dir.join("results.txt"), &trusted).is_err());
+
+        fs::write(dir.join(MANIFEST_FILE), "{\"files\":[]}").unwrap();
+        assert!(verify_manifest(&dir, &trusted).is_err());
+
+        // Without a workload key the results are left unsigned
+        if std::env::var(RESULTS_SIGNING_KEY_ENV).is_err() {
+            assert!(forged.sign_from_env().unwrap().is_none());
+            assert!(!dir.join(SIGNATURE_FILE).exists());
+            assert!(!dir.join(SIGNING_PUBLIC_KEY_FILE).exists());
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,265 @@
+// The text classification model, trained by the text-classification package and loaded
+// by the inference of the text models. It lives here because that package cannot be built
+// for wasm32-wasip1
+
+use crate::{
+    classification::Classification,
+    config::CONFIG_FILE,
+    error::AlgorithmError,
+    tokenizer::{Tokenizer, WordPieceTokenizer, TOKENIZER_FILE},
+    weights::{load_model_with, ModelSource},
+};
+use burn::{
+    module::Module,
+    nn::{
+        attention::generate_padding_mask,
+        transformer::{TransformerEncoder, TransformerEncoderConfig, TransformerEncoderInput},
+        Embedding, EmbeddingConfig, Linear, LinearConfig,
+    },
+    prelude::*,
+    record::Recorder,
+    tensor::activation::softmax,
+    tensor::{Bool, Int},
+};
+use serde::Deserialize;
+
+/// File the text models are saved to with `CompactRecorder`.
+pub static TEXT_MODEL_FILE: &str = "model.mpk";
+
+/// The fields of the text classification training config that shape the model and name
+/// its classes. The other fields saved with it are ignored.
+#[derive(Deserialize)]
+pub struct TextModelConfig {
+    pub transformer: TransformerEncoderConfig,
+    pub max_seq_length: usize,
+    pub dataset: TextDatasetConfig,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TextDatasetConfig {
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub class_names: Vec<String>,
+}
+
+impl TextModelConfig {
+    pub fn from_slice(data: &[u8]) -> Result<Self, AlgorithmError> {
+        let config: Self = serde_json::from_slice(data).map_err(|e| {
+            AlgorithmError::InvalidConfig(format!("Invalid training {}: {}", CONFIG_FILE, e))
+        })?;
+        config.class_names()?;
+
+        Ok(config)
+    }
+
+    /// Names of the classes, in label order. They default to the labels themselves.
+    pub fn class_names(&self) -> Result<Vec<String>, AlgorithmError> {
+        let dataset = &self.dataset;
+        match dataset.class_names.len() {
+            _ if dataset.labels.is_empty() => Err(AlgorithmError::InvalidConfig(format!(
+                "The training {} has no labels",
+                CONFIG_FILE
+            ))),
+            0 => Ok(dataset.labels.clone()),
+            len if len == dataset.labels.len() => Ok(dataset.class_names.clone()),
+            len => Err(AlgorithmError::InvalidConfig(format!(
+                "The training {} has {} class names for {} labels",
+                CONFIG_FILE,
+                len,
+                dataset.labels.len()
+            ))),
+        }
+    }
+}
+
+#[derive(Module, Debug)]
+pub struct TextClassificationModel<B: Backend> {
+    embedding_token: Embedding<B>,
+    embedding_position: Embedding<B>,
+    transformer: TransformerEncoder<B>,
+    output: Linear<B>,
+    num_classes: usize,
+    max_length: usize,
+}
+
+impl<B: Backend> TextClassificationModel<B> {
+    /// Builds the architecture the model was trained with.
+    pub fn new(
+        config: &TextModelConfig,
+        num_classes: usize,
+        vocab_size: usize,
+        device: &B::Device,
+    ) -> Self {
+        Self::init(
+            &config.transformer,
+            num_classes,
+            vocab_size,
+            config.max_seq_length,
+            device,
+        )
+    }
+
+    /// Builds a model classifying texts of at most `max_length` tokens, out of a vocabulary
+    /// of `vocab_size`, into `num_classes` classes.
+    pub fn init(
+        transformer: &TransformerEncoderConfig,
+        num_classes: usize,
+        vocab_size: usize,
+        max_length: usize,
+        device: &B::Device,
+    ) -> Self {
+        let d_model = transformer.d_model;
+
+        TextClassificationModel {
+            embedding_token: EmbeddingConfig::new(vocab_size, d_model).init(device),
+            embedding_position: EmbeddingConfig::new(max_length, d_model).init(device),
+            transformer: transformer.init(device),
+            output: LinearConfig::new(d_model, num_classes).init(device),
+            num_classes,
+            max_length,
+        }
+    }
+
+    /// Class scores, before the softmax, of every text of a batch of padded `tokens`, with
+    /// `mask_pad` marking the padding.
+    pub fn forward(&self, tokens: Tensor<B, 2, Int>, mask_pad: Tensor<B, 2, Bool>) -> Tensor<B, 2> {
+        let [batch_size, seq_length] = tokens.dims();
+        let device = &self.embedding_token.devices()[0];
+
+        let tokens = tokens.to_device(device);
+        let mask_pad = mask_pad.to_device(device);
+
+        let index_positions = Tensor::arange(0..seq_length as i64, device)
+            .reshape([1, seq_length])
+            .repeat(&[batch_size]);
+        let embedding_positions = self.embedding_position.forward(index_positions);
+        let embedding_tokens = self.embedding_token.forward(tokens);
+        let embedding = (embedding_positions + embedding_tokens) / 2;
+
+        let encoded = self
+            .transformer
+            .forward(TransformerEncoderInput::new(embedding).mask_pad(mask_pad));
+        let output = self.output.forward(encoded);
+
+        output
+            .slice([0..batch_size, 0..1])
+            .reshape([batch_size, self.num_classes])
+    }
+
+    /// Class probabilities of every text encoded in `tokens`, padded like the training
+    /// batches.
+    pub fn infer(&self, tokens: Vec<Vec<usize>>, pad_token: usize) -> Tensor<B, 2> {
+        let device = &self.embedding_token.devices()[0];
+        let mask = generate_padding_mask(pad_token, tokens, Some(self.max_length), device);
+
+        softmax(self.forward(mask.tensor, mask.mask), 1)
+    }
+}
+
+/// A trained text model with the tokenizer and class names it was trained with.
+pub struct TextClassifier<B: Backend> {
+    pub model: TextClassificationModel<B>,
+    pub tokenizer: WordPieceTokenizer,
+    pub class_names: Vec<String>,
+}
+
+impl<B: Backend> TextClassifier<B> {
+    /// Builds the model described by the training `config.json` saved next to the model in
+    /// `source`, loads it with `recorder`, the bytes counterpart of `CompactRecorder`, and
+    /// reads the `tokenizer.json` saved with it. The `embedded` files go with an embedded
+    /// model.
+    pub fn load<R>(
+        source: &ModelSource,
+        config_embedded: &'static [u8],
+        tokenizer_embedded: &'static [u8],
+        recorder: &R,
+        device: &B::Device,
+    ) -> Result<Self, AlgorithmError>
+    where
+        R: Recorder<B, LoadArgs = Vec<u8>>,
+    {
+        let config = TextModelConfig::from_slice(&source.sibling(CONFIG_FILE, config_embedded)?)?;
+        let class_names = config.class_names()?;
+        let tokenizer = WordPieceTokenizer::from_tokenizer_json(
+            &source.sibling(TOKENIZER_FILE, tokenizer_embedded)?,
+        )?;
+
+        let model = TextClassificationModel::new(
+            &config,
+            class_names.len(),
+            tokenizer.vocab_size(),
+            device,
+        );
+        let model = load_model_with(model, source, recorder, device).map_err(|e| {
+            AlgorithmError::InvalidConfig(format!(
+                "The model does not match the architecture in {}: {}",
+                CONFIG_FILE, e
+            ))
+        })?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            class_names,
+        })
+    }
+
+    /// Classifies every text, in order, with a single forward pass, keeping the
+    /// probability of every class and the `top_k` most likely ones.
+    pub fn classify(
+        &self,
+        texts: &[&str],
+        top_k: usize,
+    ) -> Result<Vec<Classification>, AlgorithmError> {
+        let tokens = texts
+            .iter()
+            .map(|text| self.tokenizer.encode(text))
+            .collect();
+        let output = self.model.infer(tokens, self.tokenizer.pad_token());
+        let probabilities = output.into_data().iter::<f32>().collect::<Vec<_>>();
+
+        let class_names = self
+            .class_names
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        Classification::batch(&probabilities, &class_names, top_k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(class_names: &[&str]) -> serde_json::Value {
+        serde_json::json!({
+            "dataset": {
+                "labels": ["negative", "positive"],
+                "class_names": class_names,
+                "train_file": "train.csv",
+            },
+            "transformer": TransformerEncoderConfig::new(16, 32, 2, 1),
+            "max_seq_length": 32,
+        })
+    }
+
+    #[test]
+    fn class_names_default_to_the_labels() {
+        let names = |config: serde_json::Value| {
+            TextModelConfig::from_slice(config.to_string().as_bytes())
+                .and_then(|config| config.class_names())
+        };
+
+        assert_eq!(names(config(&[])).unwrap(), vec!["negative", "positive"]);
+        assert_eq!(
+            names(config(&["bad", "good"])).unwrap(),
+            vec!["bad", "good"]
+        );
+        assert!(matches!(
+            names(config(&["bad"])),
+            Err(AlgorithmError::InvalidConfig(_))
+        ));
+        assert!(TextModelConfig::from_slice(b"{}").is_err());
+    }
+}
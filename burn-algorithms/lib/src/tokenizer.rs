@@ -0,0 +1,325 @@
+use crate::error::AlgorithmError;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+use unicode_categories::UnicodeCategories;
+
+/// Name of the tokenizer file saved next to a trained text model, and looked up inside
+/// an uploaded tokenizer archive.
+pub static TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Turns text into the token ids a text model is trained on, and back.
+pub trait Tokenizer: Send + Sync {
+    fn encode(&self, value: &str) -> Vec<usize>;
+
+    fn decode(&self, tokens: &[usize]) -> String;
+
+    fn vocab_size(&self) -> usize;
+
+    fn pad_token(&self) -> usize;
+
+    fn pad_token_value(&self) -> String {
+        self.decode(&[self.pad_token()])
+    }
+}
+
+const UNK_TOKEN: &str = "[UNK]";
+const CLS_TOKEN: &str = "[CLS]";
+const SEP_TOKEN: &str = "[SEP]";
+const PAD_TOKEN: &str = "[PAD]";
+const MASK_TOKEN: &str = "[MASK]";
+const SPECIAL_TOKENS: [&str; 5] = [PAD_TOKEN, UNK_TOKEN, CLS_TOKEN, SEP_TOKEN, MASK_TOKEN];
+const CONTINUING_PREFIX: &str = "##";
+const MAX_INPUT_CHARS_PER_WORD: usize = 100;
+
+/// A cased BERT WordPiece tokenizer in plain Rust, so that text models also build for
+/// `wasm32-wasip1`, where the `tokenizers` crate does not.
+///
+/// It produces the same ids as the `bert-base-cased` tokenizer of HuggingFace given the
+/// same vocabulary: BERT text cleaning without lowercasing or accent stripping, splits on
+/// whitespace and punctuation, greedy longest match WordPiece, and `[CLS] ... [SEP]`
+/// around every encoding.
+#[derive(Clone, Debug)]
+pub struct WordPieceTokenizer {
+    vocab: HashMap<String, usize>,
+    tokens: HashMap<usize, String>,
+    unk: usize,
+    cls: usize,
+    sep: usize,
+    pad: usize,
+}
+
+#[derive(Deserialize)]
+struct TokenizerJson {
+    model: TokenizerModel,
+}
+
+#[derive(Deserialize)]
+struct TokenizerModel {
+    vocab: HashMap<String, usize>,
+}
+
+impl WordPieceTokenizer {
+    /// Reads a `vocab.txt` with one token per line, the id being the line number.
+    pub fn from_vocab_file(path: impl AsRef<Path>) -> Result<Self, AlgorithmError> {
+        let path = path.as_ref();
+        let vocab = fs::read_to_string(path).map_err(|source| AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_vocab(&vocab)
+    }
+
+    /// Builds the tokenizer from the content of a `vocab.txt`.
+    pub fn from_vocab(vocab: &str) -> Result<Self, AlgorithmError> {
+        let vocab = vocab
+            .lines()
+            .enumerate()
+            .map(|(id, token)| (token.trim_end().to_string(), id))
+            .collect();
+        Self::new(vocab)
+    }
+
+    /// Reads the vocabulary of the WordPiece model in a HuggingFace `tokenizer.json`.
+    pub fn from_tokenizer_json(data: &[u8]) -> Result<Self, AlgorithmError> {
+        let json: TokenizerJson = serde_json::from_slice(data).map_err(|e| {
+            AlgorithmError::Tokenizer(format!("Invalid WordPiece tokenizer.json: {}", e))
+        })?;
+        Self::new(json.model.vocab)
+    }
+
+    pub fn new(vocab: HashMap<String, usize>) -> Result<Self, AlgorithmError> {
+        let id = |token: &str| {
+            vocab.get(token).copied().ok_or_else(|| {
+                AlgorithmError::Tokenizer(format!("The vocabulary has no {} token", token))
+            })
+        };
+        let (unk, cls, sep, pad) = (
+            id(UNK_TOKEN)?,
+            id(CLS_TOKEN)?,
+            id(SEP_TOKEN)?,
+            id(PAD_TOKEN)?,
+        );
+        let tokens = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+
+        Ok(Self {
+            vocab,
+            tokens,
+            unk,
+            cls,
+            sep,
+            pad,
+        })
+    }
+
+    /// Splits `text` into words and punctuation after BERT text cleaning.
+    fn pre_tokenize(text: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        for c in text.chars() {
+            if c == '\0' || c == '\u{fffd}' || is_control(c) {
+                continue;
+            }
+
+            if is_whitespace(c) {
+                words.extend(Some(std::mem::take(&mut word)).filter(|w| !w.is_empty()));
+            } else if is_punctuation(c) || is_chinese_char(c) {
+                words.extend(Some(std::mem::take(&mut word)).filter(|w| !w.is_empty()));
+                words.push(c.to_string());
+            } else {
+                word.push(c);
+            }
+        }
+        words.extend(Some(word).filter(|w| !w.is_empty()));
+
+        words
+    }
+
+    /// Greedy longest match first. A word that cannot be fully matched is unknown.
+    fn word_piece(&self, word: &str, ids: &mut Vec<usize>) {
+        if word.chars().count() > MAX_INPUT_CHARS_PER_WORD {
+            ids.push(self.unk);
+            return;
+        }
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < word.len() {
+            let mut end = word.len();
+            let piece = loop {
+                if start == end {
+                    break None;
+                }
+                let id = match start {
+                    0 => self.vocab.get(&word[start..end]),
+                    _ => self
+                        .vocab
+                        .get(&format!("{}{}", CONTINUING_PREFIX, &word[start..end])),
+                };
+                if let Some(id) = id {
+                    break Some(*id);
+                }
+                end -= word[start..end].chars().last().map_or(1, char::len_utf8);
+            };
+
+            match piece {
+                Some(id) => pieces.push(id),
+                None => {
+                    ids.push(self.unk);
+                    return;
+                }
+            }
+            start = end;
+        }
+
+        ids.extend(pieces);
+    }
+}
+
+impl Tokenizer for WordPieceTokenizer {
+    fn encode(&self, value: &str) -> Vec<usize> {
+        let mut ids = vec![self.cls];
+
+        // Special tokens written in the text are kept as is, like HuggingFace does
+        let mut rest = value;
+        while !rest.is_empty() {
+            let special = SPECIAL_TOKENS
+                .iter()
+                .filter_map(|token| rest.find(token).map(|index| (index, *token)))
+                .min_by_key(|(index, token)| (*index, std::cmp::Reverse(token.len())));
+
+            let (text, token) = match special {
+                Some((index, token)) => (&rest[..index], Some(token)),
+                None => (rest, None),
+            };
+            for word in Self::pre_tokenize(text) {
+                self.word_piece(&word, &mut ids);
+            }
+            match token {
+                Some(token) => {
+                    ids.extend(self.vocab.get(token));
+                    rest = &rest[text.len() + token.len()..];
+                }
+                None => rest = "",
+            }
+        }
+
+        ids.push(self.sep);
+        ids
+    }
+
+    fn decode(&self, tokens: &[usize]) -> String {
+        let mut text = String::new();
+        for (i, token) in tokens
+            .iter()
+            .filter_map(|id| self.tokens.get(id))
+            .enumerate()
+        {
+            let piece = match token.strip_prefix(CONTINUING_PREFIX) {
+                Some(piece) if i != 0 => piece.to_string(),
+                _ if i != 0 => format!(" {}", token),
+                _ => token.clone(),
+            };
+            text.push_str(&cleanup(&piece));
+        }
+
+        text
+    }
+
+    fn vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+
+    fn pad_token(&self) -> usize {
+        self.pad
+    }
+}
+
+/// Removes the spaces the decoder puts before punctuation and contractions.
+fn cleanup(piece: &str) -> String {
+    piece
+        .replace(" .", ".")
+        .replace(" ?", "?")
+        .replace(" !", "!")
+        .replace(" ,", ",")
+        .replace(" ' ", "'")
+        .replace(" n't", "n't")
+        .replace(" 'm", "'m")
+        .replace(" do not", " don't")
+        .replace(" 's", "'s")
+        .replace(" 've", "'ve")
+        .replace(" 're", "'re")
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r') || c.is_whitespace()
+}
+
+fn is_control(c: char) -> bool {
+    !matches!(c, '\t' | '\n' | '\r') && c.is_other()
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || c.is_punctuation()
+}
+
+/// Characters of the CJK Unified Ideographs blocks, which are split like punctuation.
+fn is_chinese_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x4E00..=0x9FFF
+            | 0x3400..=0x4DBF
+            | 0x20000..=0x2A6DF
+            | 0x2A700..=0x2B73F
+            | 0x2B740..=0x2B81F
+            | 0x2B920..=0x2CEAF
+            | 0xF900..=0xFAFF
+            | 0x2F800..=0x2FA1F
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VOCAB: &str = include_str!("../fixtures/bert-cased/vocab.txt");
+    static CORPUS: &str = include_str!("../fixtures/bert-cased/corpus.txt");
+    static TOKENIZER_JSON: &str = include_str!("../fixtures/bert-cased/tokenizer.json");
+
+    #[test]
+    fn wordpiece_matches_bert_cased_tokenizer() {
+        let wordpiece = WordPieceTokenizer::from_vocab(VOCAB).unwrap();
+        let bert = tokenizers::Tokenizer::from_bytes(TOKENIZER_JSON).unwrap();
+        assert_eq!(wordpiece.vocab_size(), bert.get_vocab_size(true));
+        assert_eq!(
+            Some(wordpiece.pad_token() as u32),
+            bert.token_to_id(PAD_TOKEN)
+        );
+
+        for line in CORPUS.lines() {
+            let expected = bert.encode(line, true).unwrap();
+            let expected_ids = expected
+                .get_ids()
+                .iter()
+                .map(|id| *id as usize)
+                .collect::<Vec<_>>();
+            let ids = wordpiece.encode(line);
+            assert_eq!(ids, expected_ids, "encoding {:?}", line);
+
+            let expected_text = bert.decode(expected.get_ids(), false).unwrap();
+            assert_eq!(wordpiece.decode(&ids), expected_text, "decoding {:?}", line);
+        }
+    }
+
+    #[test]
+    fn reads_tokenizer_json_and_rejects_incomplete_vocab() {
+        let from_json = WordPieceTokenizer::from_tokenizer_json(TOKENIZER_JSON.as_bytes()).unwrap();
+        let from_vocab = WordPieceTokenizer::from_vocab(VOCAB).unwrap();
+        assert_eq!(from_json.encode(CORPUS), from_vocab.encode(CORPUS));
+
+        let err = WordPieceTokenizer::from_vocab("[PAD]\n[UNK]\nhello\n").unwrap_err();
+        assert_eq!(err.kind(), "tokenizer");
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    results::{ResultsBundle, MANIFEST_FILE},
+    signing::SIGNATURE_FILE,
+};
+use std::{
+    error::Error,
+    io::{Read, Write},
+    net::TcpStream,
+    os::unix::net::UnixStream,
+    time::Duration,
+};
+
+/// Byte sent by the receiver once a frame has been accepted.
+pub const ACK: u8 = 0x06;
+
+/// Byte sent by the receiver when a frame is rejected.
+pub const NAK: u8 = 0x15;
+
+/// How long a sender waits for the receiver to acknowledge a frame.
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Environment variable holding the transport address training metrics are sent to.
+pub static METRICS_TRANSPORT_ENV: &str = "METRICS_TRANSPORT";
+
+/// Environment variable holding the transport address results are sent to.
+pub static RESULTS_TRANSPORT_ENV: &str = "RESULTS_TRANSPORT";
+
+/// Delivers framed messages to a receiver and waits for each one to be acknowledged.
+///
+/// Every message is written as a 4-byte big-endian length followed by the payload.
+/// The receiver answers with a single [`ACK`] byte, or [`NAK`] to reject it.
+pub trait Transport: Send {
+    fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>>;
+}
+
+pub fn write_frame(writer: &mut impl Write, data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let len = u32::try_from(data.len()).map_err(|_| "Frame is too large")?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(data)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn read_frame(reader: &mut impl Read) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+
+    let mut data = vec![0u8; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut data)?;
+
+    Ok(data)
+}
+
+pub fn write_ack(writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    writer.write_all(&[ACK])?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn read_ack(reader: &mut impl Read) -> Result<(), Box<dyn Error>> {
+    let mut ack = [0u8; 1];
+    reader.read_exact(&mut ack)?;
+
+    match ack[0] {
+        ACK => Ok(()),
+        NAK => Err("Receiver rejected the frame".into()),
+        other => Err(format!("Unexpected acknowledgement byte {:#04x}", other).into()),
+    }
+}
+
+/// Sends a frame over `stream` and waits for its acknowledgement. The stream is dropped
+/// on failure so that the next send opens a fresh connection.
+fn send_on<S: Read + Write>(
+    stream: &mut Option<S>,
+    connect: impl FnOnce() -> Result<S, Box<dyn Error>>,
+    data: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    let mut conn = match stream.take() {
+        Some(conn) => conn,
+        None => connect()?,
+    };
+
+    write_frame(&mut conn, data)?;
+    read_ack(&mut conn)?;
+    *stream = Some(conn);
+
+    Ok(())
+}
+
+pub struct UnixTransport {
+    path: String,
+    stream: Option<UnixStream>,
+}
+
+impl UnixTransport {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            stream: None,
+        }
+    }
+}
+
+impl Transport for UnixTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let path = &self.path;
+        send_on(
+            &mut self.stream,
+            || {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(ACK_TIMEOUT))?;
+                Ok(stream)
+            },
+            data,
+        )
+    }
+}
+
+pub struct TcpTransport {
+    addr: String,
+    stream: Option<TcpStream>,
+}
+
+impl TcpTransport {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            stream: None,
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let addr = &self.addr;
+        send_on(
+            &mut self.stream,
+            || {
+                let stream = TcpStream::connect(addr)?;
+                stream.set_read_timeout(Some(ACK_TIMEOUT))?;
+                stream.set_nodelay(true)?;
+                Ok(stream)
+            },
+            data,
+        )
+    }
+}
+
+/// Transport to a vsock listener, usually the cocos agent on the host (`cid` 2).
+#[cfg(target_os = "linux")]
+pub struct VsockTransport {
+    cid: u32,
+    port: u32,
+    stream: Option<std::fs::File>,
+}
+
+#[cfg(target_os = "linux")]
+impl VsockTransport {
+    pub fn new(cid: u32, port: u32) -> Self {
+        Self {
+            cid,
+            port,
+            stream: None,
+        }
+    }
+
+    fn connect(cid: u32, port: u32) -> Result<std::fs::File, Box<dyn Error>> {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        // SAFETY: the descriptor returned by `socket` is checked and owned by `fd`
+        // before any other call, `timeout` is a fully initialized `timeval` and `addr`
+        // a fully initialized `sockaddr_vm`.
+        unsafe {
+            let raw = libc::socket(libc::AF_VSOCK, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0);
+            if raw < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            let fd = OwnedFd::from_raw_fd(raw);
+
+            // Like the other transports, give up on a receiver that never acknowledges
+            let timeout = libc::timeval {
+                tv_sec: ACK_TIMEOUT.as_secs() as libc::time_t,
+                tv_usec: ACK_TIMEOUT.subsec_micros() as libc::suseconds_t,
+            };
+            let res = libc::setsockopt(
+                raw,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            );
+            if res < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+
+            let mut addr: libc::sockaddr_vm = std::mem::zeroed();
+            addr.svm_family = libc::AF_VSOCK as libc::sa_family_t;
+            addr.svm_cid = cid;
+            addr.svm_port = port;
+
+            let res = libc::connect(
+                raw,
+                &addr as *const libc::sockaddr_vm as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_vm>() as libc::socklen_t,
+            );
+            if res < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+
+            Ok(std::fs::File::from(fd))
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Transport for VsockTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let (cid, port) = (self.cid, self.port);
+        send_on(&mut self.stream, || Self::connect(cid, port), data)
+    }
+}
+
+/// Builds a transport from an address such as `unix:///run/agent.sock`,
+/// `tcp://127.0.0.1:7005` or `vsock://2:7005`.
+pub fn connect_transport(addr: &str) -> Result<Box<dyn Transport>, Box<dyn Error>> {
+    let (scheme, target) = addr
+        .split_once("://")
+        .ok_or_else(|| format!("Invalid transport address {}", addr))?;
+
+    match scheme {
+        "unix" => Ok(Box::new(UnixTransport::new(target))),
+        "tcp" => Ok(Box::new(TcpTransport::new(target))),
+        #[cfg(target_os = "linux")]
+        "vsock" => {
+            let (cid, port) = target
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid vsock address {}", addr))?;
+            Ok(Box::new(VsockTransport::new(cid.parse()?, port.parse()?)))
+        }
+        _ => Err(format!("Unsupported transport {}", scheme).into()),
+    }
+}
+
+/// Reads a transport address from the environment variable `var`, if it is set.
+pub fn transport_from_env(var: &str) -> Result<Option<Box<dyn Transport>>, Box<dyn Error>> {
+    match std::env::var(var) {
+        Ok(addr) => Ok(Some(connect_transport(&addr)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Sends the bundle manifest and its signature followed by every artifact, one frame
+/// each, in manifest order. The signature frame is empty when the results are not signed.
+pub fn send_bundle(
+    transport: &mut dyn Transport,
+    bundle: &ResultsBundle,
+) -> Result<(), Box<dyn Error>> {
+    transport.send(&std::fs::read(bundle.dir().join(MANIFEST_FILE))?)?;
+    let signature = match std::fs::read(bundle.dir().join(SIGNATURE_FILE)) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        signature => signature?,
+    };
+    transport.send(&signature)?;
+    for entry in bundle.manifest().files.iter() {
+        transport.send(&std::fs::read(bundle.dir().join(&entry.name))?)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        net::TcpListener,
+        os::unix::net::UnixListener,
+        thread::{self, JoinHandle},
+    };
+
+    fn receive<S: Read + Write + Send + 'static>(
+        mut stream: S,
+        frames: usize,
+    ) -> JoinHandle<Vec<Vec<u8>>> {
+        thread::spawn(move || {
+            (0..frames)
+                .map(|_| {
+                    let frame = read_frame(&mut stream).unwrap();
+                    write_ack(&mut stream).unwrap();
+                    frame
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn unix_transport_sends_frames() {
+        let path = std::env::temp_dir().join(format!("lib-transport-{}.sock", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let listener = UnixListener::bind(&path).unwrap();
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            receive(stream, 2).join().unwrap()
+        });
+
+        let mut transport = connect_transport(&format!("unix://{}", path.display())).unwrap();
+        transport.send(b"first").unwrap();
+        transport.send(b"").unwrap();
+
+        let frames = receiver.join().unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(frames, vec![b"first".to_vec(), Vec::new()]);
+    }
+
+    #[test]
+    fn tcp_transport_sends_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            receive(stream, 2).join().unwrap()
+        });
+
+        let mut transport = TcpTransport::new(addr.to_string());
+        transport.send(b"metrics").unwrap();
+        transport.send(&[0u8; 70_000]).unwrap();
+
+        let frames = receiver.join().unwrap();
+        assert_eq!(frames[0], b"metrics".to_vec());
+        assert_eq!(frames[1].len(), 70_000);
+    }
+
+    #[test]
+    fn tcp_transport_reports_rejected_frame() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_frame(&mut stream).unwrap();
+            stream.write_all(&[NAK]).unwrap();
+        });
+
+        let mut transport = TcpTransport::new(addr.to_string());
+        assert!(transport.send(b"rejected").is_err());
+        receiver.join().unwrap();
+    }
+
+    #[test]
+    fn connect_transport_rejects_invalid_address() {
+        assert!(connect_transport("localhost:7005").is_err());
+        assert!(connect_transport("udp://localhost:7005").is_err());
+        assert!(connect_transport("vsock://2").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{error::AlgorithmError, results::DATASETS_DIR};
+use burn::{
+    module::{Module, ModuleVisitor, ParamId},
+    prelude::*,
+    record::{BinBytesRecorder, BurnRecord, FullPrecisionSettings, Record, Recorder},
+};
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// File an algorithm saves its trained model to, and inference reads it from.
+pub static MODEL_FILE: &str = "model.bin";
+
+/// Where an inference algorithm loads its trained model from.
+///
+/// Models are embedded in the inference binaries at build time, but a retrained model can
+/// be shipped as data instead of as a new binary: a path given on the command line, or a
+/// `model.bin` uploaded to the datasets directory, takes precedence over the embedded one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModelSource {
+    File(PathBuf),
+    Embedded(&'static [u8]),
+}
+
+impl ModelSource {
+    /// Picks the model at `path`, then `model.bin` in the datasets directory, then the
+    /// `embedded` model.
+    pub fn resolve(path: Option<PathBuf>, embedded: &'static [u8]) -> Result<Self, AlgorithmError> {
+        Self::resolve_in(path, DATASETS_DIR, embedded)
+    }
+
+    /// Like [`ModelSource::resolve`] with another datasets directory. A `path` to a
+    /// directory, such as the artifact directory of a training run, stands for the
+    /// `model.bin` inside it.
+    pub fn resolve_in(
+        path: Option<PathBuf>,
+        datasets_dir: impl AsRef<Path>,
+        embedded: &'static [u8],
+    ) -> Result<Self, AlgorithmError> {
+        Self::resolve_file_in(path, datasets_dir, MODEL_FILE, embedded)
+    }
+
+    /// Like [`ModelSource::resolve_in`] for a model saved to `model_file` instead of
+    /// `model.bin`. An empty `embedded` model, for a binary built before the model was
+    /// trained, is not a fallback.
+    pub fn resolve_file_in(
+        path: Option<PathBuf>,
+        datasets_dir: impl AsRef<Path>,
+        model_file: &str,
+        embedded: &'static [u8],
+    ) -> Result<Self, AlgorithmError> {
+        if let Some(path) = path {
+            let path = match path.is_dir() {
+                true => path.join(model_file),
+                false => path,
+            };
+            if !path.is_file() {
+                return Err(AlgorithmError::MissingFile(path));
+            }
+            return Ok(Self::File(path));
+        }
+
+        let uploaded = datasets_dir.as_ref().join(model_file);
+        if uploaded.is_file() {
+            return Ok(Self::File(uploaded));
+        }
+
+        match embedded.is_empty() {
+            true => Err(AlgorithmError::MissingFile(uploaded)),
+            false => Ok(Self::Embedded(embedded)),
+        }
+    }
+
+    /// The bytes of the model record.
+    pub fn bytes(&self) -> Result<Cow<'static, [u8]>, AlgorithmError> {
+        match self {
+            Self::File(path) => read(path).map(Cow::Owned),
+            Self::Embedded(bytes) => Ok(Cow::Borrowed(bytes)),
+        }
+    }
+
+    /// Reads a file the training algorithm saved next to the model, such as the feature
+    /// scaler. It comes from the same place as the model, so that a model loaded from a
+    /// file is never paired with the artifacts embedded for another one. An empty embedded
+    /// file, for a binary built before the model was trained, is missing.
+    pub fn sibling(
+        &self,
+        name: &str,
+        embedded: &'static [u8],
+    ) -> Result<Cow<'static, [u8]>, AlgorithmError> {
+        match self {
+            Self::File(path) => read(&path.with_file_name(name)).map(Cow::Owned),
+            Self::Embedded(_) if embedded.is_empty() => {
+                Err(AlgorithmError::MissingFile(PathBuf::from(name)))
+            }
+            Self::Embedded(_) => Ok(Cow::Borrowed(embedded)),
+        }
+    }
+}
+
+/// Loads the model saved by a training algorithm with `NoStdTrainingRecorder` from
+/// `source` into `model`, which must be built with the architecture it was trained with.
+pub fn load_model<B: Backend, M: Module<B>>(
+    model: M,
+    source: &ModelSource,
+    device: &B::Device,
+) -> Result<M, AlgorithmError> {
+    let bytes = source.bytes()?.into_owned();
+    check_record::<B, M>(&bytes)?;
+
+    load_bytes(
+        model,
+        bytes,
+        &BinBytesRecorder::<FullPrecisionSettings>::default(),
+        device,
+    )
+}
+
+/// Like [`load_model`] for a model saved with another recorder, such as the
+/// `CompactRecorder` of the text models, read with its bytes counterpart `recorder`. The
+/// recorder must report bytes that do not decode as an error, which `BinBytesRecorder`
+/// does not.
+pub fn load_model_with<B, M, R>(
+    model: M,
+    source: &ModelSource,
+    recorder: &R,
+    device: &B::Device,
+) -> Result<M, AlgorithmError>
+where
+    B: Backend,
+    M: Module<B>,
+    R: Recorder<B, LoadArgs = Vec<u8>>,
+{
+    load_bytes(model, source.bytes()?.into_owned(), recorder, device)
+}
+
+fn load_bytes<B, M, R>(
+    model: M,
+    bytes: Vec<u8>,
+    recorder: &R,
+    device: &B::Device,
+) -> Result<M, AlgorithmError>
+where
+    B: Backend,
+    M: Module<B>,
+    R: Recorder<B, LoadArgs = Vec<u8>>,
+{
+    let record = recorder
+        .load(bytes, device)
+        .map_err(|e| AlgorithmError::InvalidConfig(e.to_string()))?;
+
+    load_checked(model, record)
+}
+
+/// Checks that `bytes` decode into a record of `M` saved by `BinBytesRecorder`. That
+/// recorder panics instead of failing on bytes of another layout, and a panic aborts the
+/// algorithm on wasm32-wasip1 where it cannot be caught, so the record is decoded once
+/// here before the recorder reads it.
+fn check_record<B: Backend, M: Module<B>>(bytes: &[u8]) -> Result<(), AlgorithmError> {
+    type Item<B, M> = <<M as Module<B>>::Record as Record<B>>::Item<FullPrecisionSettings>;
+
+    bincode::serde::decode_from_slice::<BurnRecord<Item<B, M>, B>, _>(
+        bytes,
+        bincode::config::standard(),
+    )
+    .map(|_| ())
+    .map_err(|e| {
+        AlgorithmError::InvalidConfig(format!("The model record has another layout: {}", e))
+    })
+}
+
+/// Loads `record` into `model`, built with the architecture the model was trained with,
+/// and checks that every parameter kept its shape. burn replaces parameters without looking at
+/// their shapes, so a record saved for another architecture would otherwise only fail at
+/// the first forward pass.
+pub fn load_checked<B: Backend, M: Module<B>>(
+    model: M,
+    record: M::Record,
+) -> Result<M, AlgorithmError> {
+    let expected = param_shapes(&model);
+    let model = model.load_record(record);
+    let loaded = param_shapes(&model);
+
+    if expected.len() != loaded.len() {
+        return Err(AlgorithmError::InvalidConfig(format!(
+            "The model record has {} parameters instead of {}",
+            loaded.len(),
+            expected.len()
+        )));
+    }
+    for (i, (expected, loaded)) in expected.iter().zip(&loaded).enumerate() {
+        if expected != loaded {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "Parameter {} of the model record has shape {:?} instead of {:?}",
+                i, loaded, expected
+            )));
+        }
+    }
+
+    Ok(model)
+}
+
+/// Shapes of the float parameters of `module`, in visiting order.
+pub fn param_shapes<B: Backend, M: Module<B>>(module: &M) -> Vec<Vec<usize>> {
+    let mut shapes = ParamShapes(Vec::new());
+    module.visit(&mut shapes);
+
+    shapes.0
+}
+
+struct ParamShapes(Vec<Vec<usize>>);
+
+impl<B: Backend> ModuleVisitor<B> for ParamShapes {
+    fn visit_float<const D: usize>(&mut self, _id: ParamId, tensor: &Tensor<B, D>) {
+        self.0.push(tensor.dims().to_vec());
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, AlgorithmError> {
+    if !path.is_file() {
+        return Err(AlgorithmError::MissingFile(path.to_path_buf()));
+    }
+
+    fs::read(path).map_err(|source| AlgorithmError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::{backend::NdArray, nn::LinearConfig};
+
+    static EMBEDDED: &[u8] = b"embedded";
+
+    #[test]
+    fn model_source_falls_back_in_order() {
+        let dir = std::env::temp_dir().join(format!("lib-weights-{}", std::process::id()));
+        let datasets = dir.join("datasets");
+        let artifacts = dir.join("artifacts");
+        fs::create_dir_all(&datasets).unwrap();
+        fs::create_dir_all(&artifacts).unwrap();
+
+        assert_eq!(
+            ModelSource::resolve_in(None, &datasets, EMBEDDED).unwrap(),
+            ModelSource::Embedded(EMBEDDED)
+        );
+
+        fs::write(datasets.join(MODEL_FILE), b"uploaded").unwrap();
+        let uploaded = ModelSource::resolve_in(None, &datasets, EMBEDDED).unwrap();
+        assert_eq!(uploaded, ModelSource::File(datasets.join(MODEL_FILE)));
+        assert_eq!(uploaded.bytes().unwrap().as_ref(), b"uploaded");
+
+        fs::write(artifacts.join(MODEL_FILE), b"retrained").unwrap();
+        let explicit =
+            ModelSource::resolve_in(Some(artifacts.clone()), &datasets, EMBEDDED).unwrap();
+        assert_eq!(explicit, ModelSource::File(artifacts.join(MODEL_FILE)));
+        assert_eq!(explicit.bytes().unwrap().as_ref(), b"retrained");
+
+        assert!(matches!(
+            ModelSource::resolve_in(Some(dir.join("missing.bin")), &datasets, EMBEDDED),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+
+        assert!(matches!(
+            ModelSource::resolve_file_in(None, &datasets, "model.mpk", &[]),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+        fs::write(artifacts.join("model.mpk"), b"compact").unwrap();
+        assert_eq!(
+            ModelSource::resolve_file_in(Some(artifacts.clone()), &datasets, "model.mpk", &[])
+                .unwrap(),
+            ModelSource::File(artifacts.join("model.mpk"))
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn model_siblings_come_from_the_model_source() {
+        let dir = std::env::temp_dir().join(format!("lib-weights-sibling-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MODEL_FILE), b"model").unwrap();
+        fs::write(dir.join("scaler.json"), b"scaler").unwrap();
+
+        let file = ModelSource::File(dir.join(MODEL_FILE));
+        assert_eq!(
+            file.sibling("scaler.json", EMBEDDED).unwrap().as_ref(),
+            b"scaler"
+        );
+        assert!(matches!(
+            file.sibling("missing.json", EMBEDDED),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+        assert_eq!(
+            ModelSource::Embedded(b"model")
+                .sibling("scaler.json", EMBEDDED)
+                .unwrap()
+                .as_ref(),
+            EMBEDDED
+        );
+        assert!(matches!(
+            ModelSource::Embedded(b"model").sibling("scaler.json", &[]),
+            Err(AlgorithmError::MissingFile(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_checked_rejects_records_of_another_architecture() {
+        let device = Default::default();
+        let trained = LinearConfig::new(4, 8).init::<NdArray>(&device);
+
+        let same = LinearConfig::new(4, 8).init::<NdArray>(&device);
+        let loaded = load_checked(same, trained.clone().into_record()).unwrap();
+        assert_eq!(param_shapes(&loaded), vec![vec![4, 8], vec![8]]);
+
+        let other = LinearConfig::new(4, 16).init::<NdArray>(&device);
+        assert!(matches!(
+            load_checked(other, trained.into_record()),
+            Err(AlgorithmError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn load_model_reports_records_of_another_layout() {
+        let device = Default::default();
+        let dir = std::env::temp_dir().join(format!("lib-weights-layout-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(MODEL_FILE);
+        let bytes = BinBytesRecorder::<FullPrecisionSettings>::default()
+            .record(
+                LinearConfig::new(4, 8)
+                    .init::<NdArray>(&device)
+                    .into_record(),
+                (),
+            )
+            .unwrap();
+        fs::write(&path, bytes).unwrap();
+        let source = ModelSource::File(path);
+
+        let same = LinearConfig::new(4, 8).init::<NdArray>(&device);
+        assert!(load_model(same, &source, &device).is_ok());
+
+        let other = LinearConfig::new(4, 16).init::<NdArray>(&device);
+        assert!(matches!(
+            load_model(other, &source, &device),
+            Err(AlgorithmError::InvalidConfig(_))
+        ));
+
+        let layers = vec![LinearConfig::new(4, 8).init::<NdArray>(&device); 2];
+        assert!(matches!(
+            load_model(layers, &source, &device),
+            Err(AlgorithmError::InvalidConfig(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
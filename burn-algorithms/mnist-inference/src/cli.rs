@@ -0,0 +1,86 @@
+// The command line of the mnist inference, run by `mnist-inference` and by
+// `burn-algorithms infer mnist`
+
+use crate::infer::{classify, inference};
+use futures::executor;
+use lib::{classification::DEFAULT_TOP_K, results::RESULTS_DIR, AlgorithmError};
+use std::path::PathBuf;
+
+static EXAMPLE: &str = "[0.0, 0.0, ..., 0.0]";
+
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} [--model <path>] [--probabilities] [--top-k <k>] <JSON input>",
+        program
+    );
+    std::process::exit(1);
+}
+
+/// Parses `args`, the arguments after `program`, classifies the image and prints the
+/// result, or saves it under cocos. Exits with the code of the error when the inference
+/// fails.
+pub fn run(program: &str, args: &[String]) {
+    let mut model_path = None;
+    let mut probabilities = false;
+    let mut top_k = DEFAULT_TOP_K;
+    let mut input = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--model" => match rest.next() {
+                Some(path) => model_path = Some(PathBuf::from(path)),
+                None => usage(program),
+            },
+            "--probabilities" => probabilities = true,
+            "--top-k" => match rest.next().map(|k| k.parse()) {
+                Some(Ok(k)) => {
+                    probabilities = true;
+                    top_k = k;
+                }
+                Some(Err(e)) => {
+                    eprintln!("Invalid --top-k: {}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => usage(program),
+        }
+    }
+    let input = match input {
+        Some(input) => input,
+        None => usage(program),
+    };
+
+    let result =
+        lib::read_input::<Vec<f32>>(input, EXAMPLE).and_then(|input| match probabilities {
+            true => executor::block_on(classify(input.as_slice(), model_path, top_k)).map(
+                |classification| {
+                    serde_json::to_string(&classification)
+                        .expect("Failed to serialize classification")
+                },
+            ),
+            false => executor::block_on(inference(input.as_slice(), model_path))
+                .map(|digit| digit.to_string()),
+        });
+    output(result);
+}
+
+fn output(result: Result<String, AlgorithmError>) {
+    match result {
+        Ok(result) => {
+            if cfg!(feature = "cocos") {
+                match lib::save_results(result) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{:}", result);
+            }
+        }
+        Err(e) => lib::exit_with_error(RESULTS_DIR, e),
+    };
+}
@@ -1,21 +1,145 @@
-use crate::model::{build_and_load_model, NDBackend};
+use crate::model::{build_and_load_model, model_source, NDBackend};
 use burn::tensor::Tensor;
+use lib::{
+    preprocessing::{normalize_mnist, MNIST_IMAGE_SIZE},
+    AlgorithmError, Classification,
+};
+use std::path::PathBuf;
 
-pub async fn inference(input: &[f32]) -> Result<i64, String> {
-    let model = Some(build_and_load_model());
+/// Names of the classes, in label order.
+pub static CLASSES: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
-    let model = match model.as_ref() {
-        Some(model) => model,
-        None => return Err("Failed to load model".to_string()),
-    };
+/// Classifies a 28x28 image given as raw pixel values between 0 and 255, row by row, as
+/// printed by `convert-image`, with the model found by [`model_source`] from `model_path`.
+pub async fn inference(input: &[f32], model_path: Option<PathBuf>) -> Result<i64, AlgorithmError> {
+    let classification = classify(input, model_path, 1).await?;
+
+    Ok(classification.label as i64)
+}
+
+/// Like [`inference`], with the probability of every digit and the `top_k` most likely
+/// ones.
+pub async fn classify(
+    input: &[f32],
+    model_path: Option<PathBuf>,
+    top_k: usize,
+) -> Result<Classification, AlgorithmError> {
+    if input.len() != MNIST_IMAGE_SIZE * MNIST_IMAGE_SIZE {
+        return Err(AlgorithmError::InvalidDataset(format!(
+            "Expected {} pixels, got {}",
+            MNIST_IMAGE_SIZE * MNIST_IMAGE_SIZE,
+            input.len()
+        )));
+    }
+
+    let source = model_source(model_path)?;
+    let model = build_and_load_model(&source)?;
 
     let device = Default::default();
 
-    let input = Tensor::<NDBackend, 1>::from_floats(input, &device).reshape([1, 28, 28]);
-    let output = model.forward(input);
+    let input = Tensor::<NDBackend, 1>::from_floats(input, &device).reshape([
+        1,
+        MNIST_IMAGE_SIZE,
+        MNIST_IMAGE_SIZE,
+    ]);
+    let output = model.forward(normalize_mnist(input));
 
     let output = burn::tensor::activation::softmax(output, 1);
-    let max_index = output.argmax(1);
+    let probabilities = output.into_data().iter::<f32>().collect::<Vec<_>>();
+
+    Classification::new(&probabilities, &CLASSES, top_k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Model;
+    use burn::{
+        data::{dataloader::batcher::Batcher, dataset::vision::MnistItem},
+        module::Module,
+        record::{BinBytesRecorder, FullPrecisionSettings, Recorder},
+    };
+    use futures::executor;
+    use lib::weights::MODEL_FILE;
+    use mnist::data::{MnistBatch, MnistBatcher};
+    use std::{fs, path::Path};
+
+    /// Saves an untrained model like the training does, to `model.bin` in `dir`.
+    fn save_model(dir: &Path) {
+        let model: Model<NDBackend> = Model::new(&Default::default());
+        let bytes = BinBytesRecorder::<FullPrecisionSettings>::default()
+            .record(model.into_record(), ())
+            .unwrap();
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(MODEL_FILE), bytes).unwrap();
+    }
+
+    #[test]
+    fn image_is_classified_like_the_training_pipeline() {
+        let dir = std::env::temp_dir().join(format!("mnist-inference-{}", std::process::id()));
+        save_model(&dir);
+        let image = concat!(env!("CARGO_MANIFEST_DIR"), "/4.png");
+        let pixels = convert_image::image_pixels(image).expect("Failed to read 4.png");
+
+        let mut item = MnistItem {
+            image: [[0f32; 28]; 28],
+            label: 4,
+        };
+        for (i, pixel) in pixels.iter().enumerate() {
+            item.image[i / 28][i % 28] = *pixel;
+        }
+        let batch: MnistBatch<NDBackend> = MnistBatcher::new(Default::default()).batch(vec![item]);
+
+        let device = Default::default();
+        let input =
+            Tensor::<NDBackend, 1>::from_floats(pixels.as_slice(), &device).reshape([1, 28, 28]);
+        normalize_mnist(input)
+            .into_data()
+            .assert_approx_eq(&batch.images.clone().into_data(), 5);
+
+        let source = model_source(Some(dir.clone())).expect("Failed to find model");
+        let expected = build_and_load_model(&source)
+            .expect("Failed to load model")
+            .forward(batch.images)
+            .argmax(1)
+            .into_scalar();
+
+        assert_eq!(
+            executor::block_on(inference(&pixels, Some(dir.clone()))).unwrap(),
+            expected
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn classification_reports_every_digit() {
+        let dir = std::env::temp_dir().join(format!("mnist-classify-{}", std::process::id()));
+        save_model(&dir);
+        let image = concat!(env!("CARGO_MANIFEST_DIR"), "/4.png");
+        let pixels = convert_image::image_pixels(image).expect("Failed to read 4.png");
+
+        let classification = executor::block_on(classify(&pixels, Some(dir.clone()), 3)).unwrap();
+        let total = classification
+            .probabilities
+            .iter()
+            .map(|class| class.probability)
+            .sum::<f32>();
+
+        assert!((total - 1.0).abs() < 1e-4);
+        assert_eq!(classification.probabilities.len(), 10);
+        assert_eq!(classification.top_k.len(), 3);
+        assert_eq!(classification.top_k[0].label, classification.label);
+        assert_eq!(
+            executor::block_on(inference(&pixels, Some(dir.clone()))).unwrap(),
+            classification.label as i64
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 
-    Ok(max_index.into_scalar())
+    #[test]
+    fn inference_rejects_wrong_image_size() {
+        assert!(executor::block_on(inference(&[0.0; 10], None)).is_err());
+    }
 }
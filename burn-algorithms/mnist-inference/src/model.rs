@@ -1,13 +1,11 @@
 // Copied from the https://github.com/ultravioletrs/ai/blob/main/burn-algorithms/mnist/src/model.rs package
 
-use burn::{
-    module::Module,
-    prelude::*,
-    record::{BinBytesRecorder, FullPrecisionSettings, Recorder},
-};
+use burn::{module::Module, prelude::*};
+use lib::{weights::load_model, AlgorithmError, ModelSource};
 use nn::{BatchNorm, PaddingConfig2d};
+use std::path::PathBuf;
 
-static STATE_ENCODED: &[u8] = include_bytes!("../../artifacts/mnist/model.bin");
+static STATE_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/model.bin"));
 const NUM_CLASSES: usize = 10;
 pub type NDBackend = burn::backend::ndarray::NdArray<f32>;
 
@@ -103,11 +101,19 @@ impl<B: Backend> ConvBlock<B> {
     }
 }
 
-pub fn build_and_load_model() -> Model<NDBackend> {
+/// Finds the model at `path`, in the datasets directory, or embedded in the binary.
+pub fn model_source(path: Option<PathBuf>) -> Result<ModelSource, AlgorithmError> {
+    ModelSource::resolve(path, STATE_ENCODED)
+}
+
+pub fn build_and_load_model(source: &ModelSource) -> Result<Model<NDBackend>, AlgorithmError> {
     let model: Model<NDBackend> = Model::new(&Default::default());
-    let record = BinBytesRecorder::<FullPrecisionSettings>::default()
-        .load(STATE_ENCODED.to_vec(), &Default::default())
-        .expect("Failed to decode state");
 
-    model.load_record(record)
+    load_model(model, source, &Default::default()).map_err(|e| match e {
+        AlgorithmError::InvalidConfig(message) => AlgorithmError::InvalidConfig(format!(
+            "The model does not match the MNIST architecture: {}",
+            message
+        )),
+        e => e,
+    })
 }
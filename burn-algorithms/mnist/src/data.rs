@@ -1,7 +1,19 @@
 use burn::{
-    data::{dataloader::batcher::Batcher, dataset::vision::MnistItem},
+    data::{
+        dataloader::batcher::Batcher,
+        dataset::{
+            vision::{MnistDataset, MnistItem},
+            Dataset, InMemDataset,
+        },
+    },
     prelude::*,
 };
+use lib::{preprocessing::normalize_mnist, AlgorithmError, DatasetDescriptor, DatasetRole};
+
+const WIDTH: usize = 28;
+const HEIGHT: usize = 28;
+const IMAGES_MAGIC: u32 = 0x0803;
+const LABELS_MAGIC: u32 = 0x0801;
 
 #[derive(Clone)]
 pub struct MnistBatcher<B: Backend> {
@@ -27,10 +39,7 @@ impl<B: Backend> Batcher<MnistItem, MnistBatch<B>> for MnistBatcher<B> {
             .map(|item| TensorData::from(item.image))
             .map(|data| Tensor::<B, 2>::from_data(data.convert::<B::FloatElem>(), &self.device))
             .map(|tensor| tensor.reshape([1, 28, 28]))
-            // normalize: make between [0,1] and make the mean =  0 and std = 1
-            // values mean=0.1307,std=0.3081 were copied from Pytorch Mist Example
-            // https://github.com/pytorch/examples/blob/54f4572509891883a947411fd7239237dd2a39c3/mnist/main.py#L122
-            .map(|tensor| ((tensor / 255) - 0.1307) / 0.3081)
+            .map(normalize_mnist)
             .collect();
 
         let targets = items
@@ -49,3 +58,87 @@ impl<B: Backend> Batcher<MnistItem, MnistBatch<B>> for MnistBatcher<B> {
         MnistBatch { images, targets }
     }
 }
+
+/// Loads an MNIST split. Under cocos the IDX files are read from the uploaded dataset,
+/// otherwise burn downloads them.
+pub fn mnist_dataset(split: &str) -> Result<Box<dyn Dataset<MnistItem>>, AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        let files = lib::dataset_files()?;
+        // The test upload is only unpacked for the test split
+        let test = match split {
+            "test" => files.open(DatasetRole::Test)?,
+            _ => None,
+        };
+        let dataset = match test {
+            Some(test) => test,
+            None => files.require(DatasetRole::Train)?,
+        };
+        return Ok(Box::new(read_idx_dataset(&dataset, split)?));
+    }
+
+    match split {
+        "train" => Ok(Box::new(MnistDataset::train())),
+        "test" => Ok(Box::new(MnistDataset::test())),
+        _ => panic!("Invalid split type"),
+    }
+}
+
+/// Reads a split from the `train-*-idx*-ubyte` or `t10k-*-idx*-ubyte` files in `dataset`.
+pub fn read_idx_dataset(
+    dataset: &DatasetDescriptor,
+    split: &str,
+) -> Result<InMemDataset<MnistItem>, AlgorithmError> {
+    let prefix = match split {
+        "train" => "train",
+        "test" => "t10k",
+        _ => panic!("Invalid split type"),
+    };
+    let images = dataset.find(&format!("{}-images-idx3-ubyte", prefix))?;
+    let labels = dataset.find(&format!("{}-labels-idx1-ubyte", prefix))?;
+
+    let images = read_idx(&images, IMAGES_MAGIC, 16)?;
+    let labels = read_idx(&labels, LABELS_MAGIC, 8)?;
+    if images.len() != labels.len() * WIDTH * HEIGHT {
+        return Err(AlgorithmError::InvalidDataset(
+            "MNIST images and labels do not match".to_string(),
+        ));
+    }
+    if let Some(label) = labels.iter().find(|label| **label > 9) {
+        return Err(AlgorithmError::InvalidLabel(label.to_string()));
+    }
+
+    let items = images
+        .chunks(WIDTH * HEIGHT)
+        .zip(labels)
+        .map(|(pixels, label)| {
+            let mut image = [[0f32; WIDTH]; HEIGHT];
+            for (i, pixel) in pixels.iter().enumerate() {
+                image[i / WIDTH][i % WIDTH] = *pixel as f32;
+            }
+            MnistItem { image, label }
+        })
+        .collect();
+
+    Ok(InMemDataset::new(items))
+}
+
+/// Returns the payload of an IDX file after checking its magic number.
+fn read_idx(
+    path: &std::path::Path,
+    magic: u32,
+    header_len: usize,
+) -> Result<Vec<u8>, AlgorithmError> {
+    let mut data = std::fs::read(path).map_err(|source| AlgorithmError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    if data.len() < header_len || u32::from_be_bytes([data[0], data[1], data[2], data[3]]) != magic
+    {
+        return Err(AlgorithmError::InvalidDataset(format!(
+            "{} is not an IDX file",
+            path.display()
+        )));
+    }
+
+    Ok(data.split_off(header_len))
+}
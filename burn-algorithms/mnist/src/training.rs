@@ -1,10 +1,13 @@
-use crate::{data::MnistBatcher, model::Model};
+use crate::{
+    data::{mnist_dataset, MnistBatcher},
+    model::Model,
+};
 
 use burn::{
-    data::{dataloader::DataLoaderBuilder, dataset::vision::MnistDataset},
+    data::dataloader::DataLoaderBuilder,
     optim::{decay::WeightDecayConfig, AdamConfig},
     prelude::*,
-    record::{CompactRecorder, NoStdTrainingRecorder},
+    record::{BinBytesRecorder, CompactRecorder, FullPrecisionSettings, NoStdTrainingRecorder},
     tensor::backend::AutodiffBackend,
     train::{
         metric::{
@@ -14,6 +17,7 @@ use burn::{
         LearnerBuilder, MetricEarlyStoppingStrategy, StoppingCondition,
     },
 };
+use lib::AlgorithmError;
 
 #[cfg(feature = "cocos")]
 static ARTIFACT_DIR: &str = "results";
@@ -45,11 +49,31 @@ fn create_artifact_dir(artifact_dir: &str) {
 }
 
 pub fn run<B: AutodiffBackend>(device: B::Device) {
+    if let Err(err) = train::<B>(device) {
+        lib::exit_with_error(ARTIFACT_DIR, err);
+    }
+}
+
+fn train<B: AutodiffBackend>(device: B::Device) -> Result<(), AlgorithmError> {
     create_artifact_dir(ARTIFACT_DIR);
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(ARTIFACT_DIR)?;
+    }
     let config_optimizer = AdamConfig::new().with_weight_decay(Some(WeightDecayConfig::new(5e-5)));
-    let config = MnistTrainingConfig::new(config_optimizer);
+    let config = lib::load_config(MnistTrainingConfig::new(config_optimizer))?;
     B::seed(config.seed);
 
+    let model = Model::new(&device);
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &BinBytesRecorder::<FullPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
+
     let batcher_train = MnistBatcher::<B>::new(device.clone());
     let batcher_valid = MnistBatcher::<B::InnerBackend>::new(device.clone());
 
@@ -57,12 +81,12 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
         .batch_size(config.batch_size)
         .shuffle(config.seed)
         .num_workers(config.num_workers)
-        .build(MnistDataset::train());
+        .build(mnist_dataset("train")?);
     let dataloader_test = DataLoaderBuilder::new(batcher_valid)
         .batch_size(config.batch_size)
         .shuffle(config.seed)
         .num_workers(config.num_workers)
-        .build(MnistDataset::test());
+        .build(mnist_dataset("test")?);
 
     let learner = if cfg!(feature = "cocos") {
         LearnerBuilder::new(ARTIFACT_DIR)
@@ -81,12 +105,8 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             ))
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
-            .renderer(lib::EmptyMetricsRenderer)
-            .build(
-                Model::new(&device),
-                config.optimizer.init(),
-                config.learning_rate,
-            )
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
+            .build(model, config.optimizer.init(), config.learning_rate)
     } else {
         LearnerBuilder::new(ARTIFACT_DIR)
             .metric_train_numeric(AccuracyMetric::new())
@@ -105,11 +125,7 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
             .summary()
-            .build(
-                Model::new(&device),
-                config.optimizer.init(),
-                config.learning_rate,
-            )
+            .build(model, config.optimizer.init(), config.learning_rate)
     };
 
     let model_trained = learner.fit(dataloader_train, dataloader_test);
@@ -124,4 +140,10 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             &NoStdTrainingRecorder::new(),
         )
         .expect("Failed to save trained model");
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(ARTIFACT_DIR).expect("Failed to write results manifest");
+    }
+
+    Ok(())
 }
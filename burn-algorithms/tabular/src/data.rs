@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use burn::{
+    data::{
+        dataloader::batcher::Batcher,
+        dataset::{transform::ShuffledDataset, Dataset, InMemDataset},
+    },
+    prelude::*,
+};
+use csv::StringRecord;
+use lib::AlgorithmError;
+
+use crate::schema::{Columns, TabularSchema};
+
+/// A row encoded by the schema. `target` is the class index for classification, and the
+/// scaled label for regression.
+#[derive(Clone, Debug)]
+pub struct TabularItem {
+    pub inputs: Vec<f32>,
+    pub target: f32,
+}
+
+pub struct TabularDataset {
+    dataset: InMemDataset<TabularItem>,
+}
+
+impl Dataset<TabularItem> for TabularDataset {
+    fn get(&self, index: usize) -> Option<TabularItem> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+impl TabularDataset {
+    /// Reads the train and test datasets described by `schema` from the files in
+    /// `data_dir`, or from the uploaded datasets when it is `None`. The schema is fitted
+    /// on the train records before both are encoded.
+    pub fn splits(
+        schema: &mut TabularSchema,
+        data_dir: Option<&Path>,
+    ) -> Result<(Self, Self), AlgorithmError> {
+        let (train_file, test_file) = lib::datasets::train_test_files(
+            &schema.train_file,
+            schema.test_file.as_deref(),
+            data_dir,
+        )?;
+
+        let (headers, train) = lib::datasets::read_csv_records(&train_file, schema.delimiter)?;
+        let columns = schema.columns(&headers)?;
+        schema.fit(&columns, &train)?;
+
+        let train = encode(schema, &columns, &train)?;
+        match test_file {
+            Some(test_file) => {
+                let (headers, test) =
+                    lib::datasets::read_csv_records(&test_file, schema.delimiter)?;
+                let test = encode(schema, &schema.columns(&headers)?, &test)?;
+
+                Ok((Self::new(train), Self::new(test)))
+            }
+            None => Ok(Self::split(train, schema.train_ratio)),
+        }
+    }
+
+    fn new(items: Vec<TabularItem>) -> Self {
+        Self {
+            dataset: InMemDataset::new(items),
+        }
+    }
+
+    /// Shuffles the items with a fixed seed and splits them between a train and a test
+    /// dataset.
+    fn split(items: Vec<TabularItem>, train_ratio: f64) -> (Self, Self) {
+        let len = items.len();
+        let at = ((len as f64 * train_ratio) as usize).min(len);
+        let items = ShuffledDataset::with_seed(InMemDataset::new(items), 42)
+            .iter()
+            .collect::<Vec<_>>();
+        let (train, test) = items.split_at(at);
+
+        (Self::new(train.to_vec()), Self::new(test.to_vec()))
+    }
+}
+
+fn encode(
+    schema: &TabularSchema,
+    columns: &Columns,
+    records: &[StringRecord],
+) -> Result<Vec<TabularItem>, AlgorithmError> {
+    records
+        .iter()
+        .map(|record| {
+            Ok(TabularItem {
+                inputs: schema.encode(columns, record)?,
+                target: schema.target(columns, record)?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct TabularBatcher<B: Backend> {
+    device: B::Device,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClassificationBatch<B: Backend> {
+    pub inputs: Tensor<B, 2>,
+    pub targets: Tensor<B, 1, Int>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RegressionBatch<B: Backend> {
+    pub inputs: Tensor<B, 2>,
+    pub targets: Tensor<B, 1>,
+}
+
+impl<B: Backend> TabularBatcher<B> {
+    pub fn new(device: B::Device) -> Self {
+        Self { device }
+    }
+
+    fn inputs(&self, items: &[TabularItem]) -> Tensor<B, 2> {
+        let inputs = items
+            .iter()
+            .map(|item| Tensor::<B, 1>::from_floats(item.inputs.as_slice(), &self.device))
+            .map(Tensor::unsqueeze)
+            .collect();
+
+        Tensor::cat(inputs, 0)
+    }
+}
+
+impl<B: Backend> Batcher<TabularItem, ClassificationBatch<B>> for TabularBatcher<B> {
+    fn batch(&self, items: Vec<TabularItem>) -> ClassificationBatch<B> {
+        let targets = items
+            .iter()
+            .map(|item| {
+                Tensor::<B, 1, Int>::from_data(
+                    TensorData::from([(item.target as i64).elem::<B::IntElem>()]),
+                    &self.device,
+                )
+            })
+            .collect();
+
+        ClassificationBatch {
+            inputs: self.inputs(&items),
+            targets: Tensor::cat(targets, 0),
+        }
+    }
+}
+
+impl<B: Backend> Batcher<TabularItem, RegressionBatch<B>> for TabularBatcher<B> {
+    fn batch(&self, items: Vec<TabularItem>) -> RegressionBatch<B> {
+        let targets = items
+            .iter()
+            .map(|item| Tensor::<B, 1>::from_floats([item.target], &self.device))
+            .collect();
+
+        RegressionBatch {
+            inputs: self.inputs(&items),
+            targets: Tensor::cat(targets, 0),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod data;
+pub mod model;
+pub mod schema;
+pub mod training;
@@ -0,0 +1,35 @@
+#[cfg(not(feature = "wgpu"))]
+mod ndarray {
+    use burn::backend::{
+        ndarray::{NdArray, NdArrayDevice},
+        Autodiff,
+    };
+
+    use tabular::training;
+
+    pub fn run() {
+        let devices = NdArrayDevice::default();
+        training::run::<Autodiff<NdArray>>(devices);
+    }
+}
+
+#[cfg(feature = "wgpu")]
+mod wgpu {
+    use burn::backend::{
+        wgpu::{Wgpu, WgpuDevice},
+        Autodiff,
+    };
+    use tabular::training;
+
+    pub fn run() {
+        let device = WgpuDevice::default();
+        training::run::<Autodiff<Wgpu>>(device);
+    }
+}
+
+fn main() {
+    #[cfg(not(feature = "wgpu"))]
+    ndarray::run();
+    #[cfg(feature = "wgpu")]
+    wgpu::run();
+}
@@ -0,0 +1,122 @@
+use crate::data::{ClassificationBatch, RegressionBatch};
+use burn::{
+    nn::{
+        loss::{CrossEntropyLossConfig, MseLoss, Reduction::Mean},
+        Linear, LinearConfig, Relu,
+    },
+    prelude::*,
+    tensor::backend::AutodiffBackend,
+    train::{ClassificationOutput, RegressionOutput, TrainOutput, TrainStep, ValidStep},
+};
+
+/// A linear model when `hidden_size` is 0, like the Wine Quality one, and otherwise a
+/// two hidden layer MLP, like the Iris one.
+#[derive(Module, Debug)]
+pub struct TabularModel<B: Backend> {
+    layers: Vec<Linear<B>>,
+    activation: Relu,
+}
+
+#[derive(Config)]
+pub struct TabularModelConfig {
+    pub input_size: usize,
+    pub hidden_size: usize,
+    pub output_size: usize,
+}
+
+impl TabularModelConfig {
+    pub fn init<B: Backend>(&self, device: &B::Device) -> TabularModel<B> {
+        let sizes = match self.hidden_size {
+            0 => vec![self.input_size, self.output_size],
+            hidden_size => vec![
+                self.input_size,
+                hidden_size,
+                hidden_size / 2,
+                self.output_size,
+            ],
+        };
+        let layers = sizes
+            .windows(2)
+            .map(|size| {
+                LinearConfig::new(size[0], size[1])
+                    .with_bias(true)
+                    .init(device)
+            })
+            .collect();
+
+        TabularModel {
+            layers,
+            activation: Relu::new(),
+        }
+    }
+}
+
+impl<B: Backend> TabularModel<B> {
+    pub fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+        let mut x = input.detach();
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i > 0 {
+                x = self.activation.forward(x);
+            }
+            x = layer.forward(x);
+        }
+
+        x
+    }
+
+    pub fn forward_classification(&self, item: ClassificationBatch<B>) -> ClassificationOutput<B> {
+        let targets = item.targets;
+        let output = self.forward(item.inputs);
+        let loss = CrossEntropyLossConfig::new()
+            .init(&output.device())
+            .forward(output.clone(), targets.clone());
+
+        ClassificationOutput {
+            loss,
+            output,
+            targets,
+        }
+    }
+
+    pub fn forward_regression(&self, item: RegressionBatch<B>) -> RegressionOutput<B> {
+        let targets: Tensor<B, 2> = item.targets.unsqueeze_dim(1);
+        let output = self.forward(item.inputs);
+        let loss = MseLoss::new().forward(output.clone(), targets.clone(), Mean);
+
+        RegressionOutput {
+            loss,
+            output,
+            targets,
+        }
+    }
+}
+
+impl<B: AutodiffBackend> TrainStep<ClassificationBatch<B>, ClassificationOutput<B>>
+    for TabularModel<B>
+{
+    fn step(&self, item: ClassificationBatch<B>) -> TrainOutput<ClassificationOutput<B>> {
+        let item = self.forward_classification(item);
+
+        TrainOutput::new(self, item.loss.backward(), item)
+    }
+}
+
+impl<B: Backend> ValidStep<ClassificationBatch<B>, ClassificationOutput<B>> for TabularModel<B> {
+    fn step(&self, item: ClassificationBatch<B>) -> ClassificationOutput<B> {
+        self.forward_classification(item)
+    }
+}
+
+impl<B: AutodiffBackend> TrainStep<RegressionBatch<B>, RegressionOutput<B>> for TabularModel<B> {
+    fn step(&self, item: RegressionBatch<B>) -> TrainOutput<RegressionOutput<B>> {
+        let item = self.forward_regression(item);
+
+        TrainOutput::new(self, item.loss.backward(), item)
+    }
+}
+
+impl<B: Backend> ValidStep<RegressionBatch<B>, RegressionOutput<B>> for TabularModel<B> {
+    fn step(&self, item: RegressionBatch<B>) -> RegressionOutput<B> {
+        self.forward_regression(item)
+    }
+}
@@ -0,0 +1,438 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use csv::StringRecord;
+use lib::{AlgorithmError, FeatureScaler, ScalerKind};
+use serde::{Deserialize, Serialize};
+
+/// Whether the label is a class or a number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Task {
+    Classification,
+    Regression,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeatureType {
+    /// A number, scaled to `[0, 1]` with the range of the training data.
+    Numeric,
+    /// One of `values`, one-hot encoded.
+    Categorical,
+}
+
+/// A column used as model input.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Feature {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub feature_type: FeatureType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+/// The column the model predicts. Classification labels must be one of `classes`, and
+/// regression labels are scaled to `[0, 1]` like numeric features.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Label {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+}
+
+/// Describes a tabular CSV dataset, for example:
+///
+/// ```json
+/// {
+///   "task": "classification",
+///   "features": [
+///     { "name": "SepalLengthCm", "type": "numeric" },
+///     { "name": "Color", "type": "categorical", "values": ["red", "white"] }
+///   ],
+///   "label": { "name": "Species" },
+///   "train_file": "Iris.csv"
+/// }
+/// ```
+///
+/// Categorical values and classes that are left out, as well as the scalers, are taken
+/// from the training data by [`TabularSchema::fit`], and the fitted schema is saved with
+/// the model.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TabularSchema {
+    pub task: Task,
+    pub features: Vec<Feature>,
+    pub label: Label,
+    /// See [`lib::datasets::train_test_files`].
+    pub train_file: String,
+    #[serde(default)]
+    pub test_file: Option<String>,
+    #[serde(default = "default_train_ratio")]
+    pub train_ratio: f64,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Min-max scaler of the numeric features, in schema order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaler: Option<FeatureScaler>,
+    /// Min-max scaler of a regression label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_scaler: Option<FeatureScaler>,
+}
+
+fn default_train_ratio() -> f64 {
+    0.8
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/// Positions of the schema columns in the CSV header.
+pub struct Columns {
+    features: Vec<usize>,
+    label: usize,
+}
+
+impl TabularSchema {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AlgorithmError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(AlgorithmError::MissingFile(path.to_path_buf()));
+        }
+        let data = fs::read(path).map_err(|source| AlgorithmError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let schema: Self = serde_json::from_slice(&data).map_err(|e| {
+            AlgorithmError::InvalidConfig(format!("Invalid schema {}: {}", path.display(), e))
+        })?;
+
+        if schema.features.is_empty() {
+            return Err(AlgorithmError::InvalidConfig(
+                "The schema lists no feature columns".to_string(),
+            ));
+        }
+        if !(schema.train_ratio > 0.0 && schema.train_ratio < 1.0) {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The schema train_ratio {} is not between 0 and 1",
+                schema.train_ratio
+            )));
+        }
+        let numeric = schema
+            .features
+            .iter()
+            .filter(|feature| feature.feature_type == FeatureType::Numeric)
+            .count();
+        if let Some(scaler) = schema.scaler.as_ref().filter(|s| s.len() != numeric) {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The schema scaler has {} features for {} numeric columns",
+                scaler.len(),
+                numeric
+            )));
+        }
+        if schema.label_scaler.as_ref().is_some_and(|s| s.len() != 1) {
+            return Err(AlgorithmError::InvalidConfig(
+                "The schema label scaler must have a single feature".to_string(),
+            ));
+        }
+
+        Ok(schema)
+    }
+
+    /// Number of model inputs once categorical features are one-hot encoded.
+    pub fn input_size(&self) -> usize {
+        self.features
+            .iter()
+            .map(|feature| match feature.feature_type {
+                FeatureType::Numeric => 1,
+                FeatureType::Categorical => feature.values.len(),
+            })
+            .sum()
+    }
+
+    /// Number of model outputs, one per class or a single value.
+    pub fn output_size(&self) -> usize {
+        match self.task {
+            Task::Classification => self.label.classes.len(),
+            Task::Regression => 1,
+        }
+    }
+
+    pub fn columns(&self, headers: &StringRecord) -> Result<Columns, AlgorithmError> {
+        let column = |name: &str| {
+            headers.iter().position(|h| h == name).ok_or_else(|| {
+                AlgorithmError::InvalidDataset(format!("The dataset has no column {:?}", name))
+            })
+        };
+
+        Ok(Columns {
+            features: self
+                .features
+                .iter()
+                .map(|feature| column(&feature.name))
+                .collect::<Result<_, _>>()?,
+            label: column(&self.label.name)?,
+        })
+    }
+
+    /// Fills in the scalers, categorical values and classes missing from the schema with
+    /// the ones found in the training `records`.
+    pub fn fit(
+        &mut self,
+        columns: &Columns,
+        records: &[StringRecord],
+    ) -> Result<(), AlgorithmError> {
+        for (feature, column) in self.features.iter_mut().zip(&columns.features) {
+            if feature.feature_type == FeatureType::Categorical && feature.values.is_empty() {
+                feature.values =
+                    distinct(records.iter().map(|r| r.get(*column).unwrap_or_default()));
+            }
+        }
+        if self.scaler.is_none() {
+            let rows = records
+                .iter()
+                .map(|record| self.numeric_values(columns, record))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.scaler = Some(FeatureScaler::fit(ScalerKind::MinMax, rows)?);
+        }
+
+        let labels = records
+            .iter()
+            .map(|r| r.get(columns.label).unwrap_or_default());
+        match self.task {
+            Task::Classification if self.label.classes.is_empty() => {
+                self.label.classes = distinct(labels);
+            }
+            Task::Classification => {}
+            Task::Regression if self.label_scaler.is_none() => {
+                let rows = labels
+                    .map(|label| parse(&self.label.name, label).map(|value| [value]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.label_scaler = Some(FeatureScaler::fit(ScalerKind::MinMax, rows)?);
+            }
+            Task::Regression => {}
+        }
+
+        Ok(())
+    }
+
+    /// Values of the numeric features of a record, in schema order.
+    fn numeric_values(
+        &self,
+        columns: &Columns,
+        record: &StringRecord,
+    ) -> Result<Vec<f64>, AlgorithmError> {
+        self.features
+            .iter()
+            .zip(&columns.features)
+            .filter(|(feature, _)| feature.feature_type == FeatureType::Numeric)
+            .map(|(feature, column)| parse(&feature.name, record.get(*column).unwrap_or_default()))
+            .collect()
+    }
+
+    /// Encodes the features of a record into model inputs.
+    pub fn encode(
+        &self,
+        columns: &Columns,
+        record: &StringRecord,
+    ) -> Result<Vec<f32>, AlgorithmError> {
+        let mut numeric =
+            scale(self.scaler.as_ref(), &self.numeric_values(columns, record)?).into_iter();
+        let mut inputs = Vec::with_capacity(self.input_size());
+        for (feature, column) in self.features.iter().zip(&columns.features) {
+            let value = record.get(*column).unwrap_or_default();
+            match feature.feature_type {
+                FeatureType::Numeric => inputs.extend(numeric.next()),
+                FeatureType::Categorical => {
+                    let index =
+                        feature
+                            .values
+                            .iter()
+                            .position(|v| v == value)
+                            .ok_or_else(|| {
+                                AlgorithmError::InvalidDataset(format!(
+                                    "Unknown value {:?} for column {:?}",
+                                    value, feature.name
+                                ))
+                            })?;
+                    inputs.extend((0..feature.values.len()).map(|i| (i == index) as u8 as f32));
+                }
+            }
+        }
+
+        Ok(inputs)
+    }
+
+    /// Encodes the label of a record: the class index, or the scaled value.
+    pub fn target(&self, columns: &Columns, record: &StringRecord) -> Result<f32, AlgorithmError> {
+        let value = record.get(columns.label).unwrap_or_default();
+        match self.task {
+            Task::Classification => self
+                .label
+                .classes
+                .iter()
+                .position(|class| class == value)
+                .map(|index| index as f32)
+                .ok_or_else(|| AlgorithmError::InvalidLabel(value.to_string())),
+            Task::Regression => Ok(scale(
+                self.label_scaler.as_ref(),
+                &[parse(&self.label.name, value)?],
+            )[0]),
+        }
+    }
+}
+
+fn parse(column: &str, value: &str) -> Result<f64, AlgorithmError> {
+    value.trim().parse().map_err(|_| {
+        AlgorithmError::InvalidDataset(format!(
+            "Value {:?} of column {:?} is not a number",
+            value, column
+        ))
+    })
+}
+
+fn distinct<'a>(values: impl Iterator<Item = &'a str>) -> Vec<String> {
+    values
+        .map(String::from)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Scales `values` with `scaler`, or keeps them as they are before the schema is fitted.
+fn scale(scaler: Option<&FeatureScaler>, values: &[f64]) -> Vec<f32> {
+    match scaler {
+        Some(scaler) => scaler.transform(values),
+        None => values.iter().map(|value| *value as f32).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(task: &str) -> TabularSchema {
+        serde_json::from_value(serde_json::json!({
+            "task": task,
+            "features": [
+                { "name": "size", "type": "numeric" },
+                { "name": "color", "type": "categorical" }
+            ],
+            "label": { "name": "label" },
+            "train_file": "train.csv"
+        }))
+        .unwrap()
+    }
+
+    fn records(rows: &[[&str; 3]]) -> Vec<StringRecord> {
+        rows.iter()
+            .map(|row| StringRecord::from(row.to_vec()))
+            .collect()
+    }
+
+    fn headers() -> StringRecord {
+        StringRecord::from(vec!["label", "size", "color"])
+    }
+
+    #[test]
+    fn fit_scales_numeric_and_one_hot_encodes_categorical_features() {
+        let mut schema = schema("classification");
+        let columns = schema.columns(&headers()).unwrap();
+        let train = records(&[["b", "2", "red"], ["a", "6", "white"], ["b", "4", "red"]]);
+        schema.fit(&columns, &train).unwrap();
+
+        assert_eq!(schema.features[1].values, vec!["red", "white"]);
+        assert_eq!(schema.label.classes, vec!["a", "b"]);
+        assert_eq!(schema.input_size(), 3);
+        assert_eq!(schema.output_size(), 2);
+
+        assert_eq!(
+            schema.encode(&columns, &train[0]).unwrap(),
+            vec![0.0, 1.0, 0.0]
+        );
+        assert_eq!(
+            schema.encode(&columns, &train[1]).unwrap(),
+            vec![1.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            schema.encode(&columns, &train[2]).unwrap(),
+            vec![0.5, 1.0, 0.0]
+        );
+        assert_eq!(schema.target(&columns, &train[0]).unwrap(), 1.0);
+        assert_eq!(schema.target(&columns, &train[1]).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn unknown_category_and_class_are_rejected() {
+        let mut schema = schema("classification");
+        let columns = schema.columns(&headers()).unwrap();
+        schema
+            .fit(
+                &columns,
+                &records(&[["a", "1", "red"], ["b", "2", "white"]]),
+            )
+            .unwrap();
+
+        let record = StringRecord::from(vec!["a", "1", "blue"]);
+        let err = schema.encode(&columns, &record).unwrap_err();
+        assert_eq!(err.kind(), "invalid_dataset");
+        assert!(err.to_string().contains("blue"));
+
+        let record = StringRecord::from(vec!["c", "1", "red"]);
+        let err = schema.target(&columns, &record).unwrap_err();
+        assert_eq!(err.kind(), "invalid_label");
+        assert!(err.to_string().contains('c'));
+    }
+
+    #[test]
+    fn regression_label_is_scaled_with_the_training_range() {
+        let mut schema = schema("regression");
+        let columns = schema.columns(&headers()).unwrap();
+        let train = records(&[["3", "1", "red"], ["9", "2", "red"], ["5", "3", "red"]]);
+        schema.fit(&columns, &train).unwrap();
+
+        assert!(schema.label.classes.is_empty());
+        assert_eq!(schema.output_size(), 1);
+        let targets = train
+            .iter()
+            .map(|record| schema.target(&columns, record).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(targets, vec![0.0, 1.0, 1.0 / 3.0]);
+
+        let record = StringRecord::from(vec!["good", "1", "red"]);
+        let err = schema.target(&columns, &record).unwrap_err();
+        assert_eq!(err.kind(), "invalid_dataset");
+    }
+
+    #[test]
+    fn missing_column_is_reported() {
+        let schema = schema("classification");
+        let headers = StringRecord::from(vec!["label", "size"]);
+
+        let err = schema.columns(&headers).err().unwrap();
+        assert_eq!(err.kind(), "invalid_dataset");
+        assert!(err.to_string().contains("color"));
+    }
+
+    #[test]
+    fn train_ratio_outside_0_and_1_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("tabular-schema-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("schema.json");
+
+        let mut value = serde_json::to_value(schema("classification")).unwrap();
+        for ratio in [0.0, 1.0, 1.5] {
+            value["train_ratio"] = serde_json::json!(ratio);
+            fs::write(&path, value.to_string()).unwrap();
+            let err = TabularSchema::load(&path).unwrap_err();
+            assert_eq!(err.kind(), "invalid_config");
+        }
+        value["train_ratio"] = serde_json::json!(0.5);
+        fs::write(&path, value.to_string()).unwrap();
+        assert_eq!(TabularSchema::load(&path).unwrap().train_ratio, 0.5);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
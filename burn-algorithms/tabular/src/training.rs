@@ -0,0 +1,268 @@
+use crate::data::{ClassificationBatch, RegressionBatch, TabularBatcher, TabularDataset};
+use crate::model::{TabularModel, TabularModelConfig};
+use crate::schema::{TabularSchema, Task};
+use burn::optim::decay::WeightDecayConfig;
+use burn::train::metric::AccuracyMetric;
+use burn::{
+    data::dataloader::DataLoaderBuilder,
+    optim::AdamConfig,
+    prelude::*,
+    record::{BinBytesRecorder, CompactRecorder, FullPrecisionSettings, NoStdTrainingRecorder},
+    tensor::backend::AutodiffBackend,
+    train::{
+        metric::store::{Aggregate, Direction, Split},
+        metric::LossMetric,
+        LearnerBuilder, MetricEarlyStoppingStrategy, StoppingCondition,
+    },
+};
+use lib::{results::MANIFEST_FILE, weights::MODEL_FILE, AlgorithmError};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "cocos")]
+static ARTIFACT_DIR: &str = "results";
+
+#[cfg(not(feature = "cocos"))]
+static ARTIFACT_DIR: &str = "artifacts/tabular/";
+
+#[derive(Config)]
+pub struct ExpConfig {
+    pub optimizer: AdamConfig,
+
+    /// The dataset schema, relative to the datasets directory.
+    #[config(default = "String::from(\"schema.json\")")]
+    pub schema_file: String,
+    #[config(default = 100)]
+    pub num_epochs: usize,
+    #[config(default = 5)]
+    pub stop_after_n_epochs: usize,
+    #[config(default = 128)]
+    pub batch_size: usize,
+    #[config(default = 2)]
+    pub num_workers: usize,
+    #[config(default = 42)]
+    pub seed: u64,
+    /// Size of the first hidden layer, or 0 for a linear model.
+    #[config(default = 128)]
+    pub hidden_size: usize,
+    #[config(default = 5e-3)]
+    pub learning_rate: f64,
+}
+
+pub fn run<B: AutodiffBackend>(device: B::Device) {
+    if let Err(err) = train::<B>(device) {
+        lib::exit_with_error(ARTIFACT_DIR, err);
+    }
+}
+
+fn train<B: AutodiffBackend>(device: B::Device) -> Result<(), AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(ARTIFACT_DIR)?;
+    }
+
+    let optimizer = AdamConfig::new().with_weight_decay(Some(WeightDecayConfig::new(5e-5)));
+    let config = lib::load_config(ExpConfig::new(optimizer))?;
+
+    let mut schema =
+        TabularSchema::load(Path::new(lib::results::DATASETS_DIR).join(&config.schema_file))?;
+    let (train_dataset, test_dataset) = TabularDataset::splits(&mut schema, None)?;
+
+    #[cfg(not(feature = "cocos"))]
+    {
+        use burn::data::dataset::Dataset;
+
+        println!("Train Dataset Size: {}", train_dataset.len());
+        println!("Test Dataset Size: {}", test_dataset.len());
+    }
+
+    let model = TabularModelConfig::new(
+        schema.input_size(),
+        config.hidden_size,
+        schema.output_size(),
+    )
+    .init::<B>(&device);
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &BinBytesRecorder::<FullPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
+    B::seed(config.seed);
+
+    let model_trained = match schema.task {
+        Task::Classification => {
+            fit_classification(&config, model, train_dataset, test_dataset, device)
+        }
+        Task::Regression => fit_regression(&config, model, train_dataset, test_dataset, device),
+    };
+
+    let artifact = |name: &str| Path::new(ARTIFACT_DIR).join(name);
+    config
+        .save(artifact("config.json"))
+        .map_err(|source| AlgorithmError::Io {
+            path: artifact("config.json"),
+            source,
+        })?;
+
+    std::fs::write(
+        artifact("schema.json"),
+        serde_json::to_vec_pretty(&schema).expect("Failed to serialize schema"),
+    )
+    .map_err(|source| AlgorithmError::Io {
+        path: artifact("schema.json"),
+        source,
+    })?;
+
+    model_trained
+        .save_file(artifact("model"), &NoStdTrainingRecorder::new())
+        .map_err(|e| save_error(artifact(MODEL_FILE), e))?;
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(ARTIFACT_DIR)
+            .map_err(|e| save_error(artifact(MANIFEST_FILE), e))?;
+    }
+
+    Ok(())
+}
+
+fn save_error(path: PathBuf, err: impl ToString) -> AlgorithmError {
+    AlgorithmError::Io {
+        path,
+        source: io::Error::other(err.to_string()),
+    }
+}
+
+fn fit_classification<B: AutodiffBackend>(
+    config: &ExpConfig,
+    model: TabularModel<B>,
+    train_dataset: TabularDataset,
+    test_dataset: TabularDataset,
+    device: B::Device,
+) -> TabularModel<B> {
+    let batcher_train = TabularBatcher::<B>::new(device.clone());
+
+    let batcher_test = TabularBatcher::<B::InnerBackend>::new(device.clone());
+
+    let dataloader_train = DataLoaderBuilder::<_, ClassificationBatch<B>>::new(batcher_train)
+        .batch_size(config.batch_size)
+        .shuffle(config.seed)
+        .num_workers(config.num_workers)
+        .build(train_dataset);
+
+    let dataloader_test =
+        DataLoaderBuilder::<_, ClassificationBatch<B::InnerBackend>>::new(batcher_test)
+            .batch_size(config.batch_size)
+            .shuffle(config.seed)
+            .num_workers(config.num_workers)
+            .build(test_dataset);
+
+    let learner = if cfg!(feature = "cocos") {
+        LearnerBuilder::new(ARTIFACT_DIR)
+            .metric_train_numeric(AccuracyMetric::new())
+            .metric_valid_numeric(AccuracyMetric::new())
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .early_stopping(MetricEarlyStoppingStrategy::new::<LossMetric<B>>(
+                Aggregate::Mean,
+                Direction::Lowest,
+                Split::Valid,
+                StoppingCondition::NoImprovementSince {
+                    n_epochs: config.stop_after_n_epochs,
+                },
+            ))
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
+            .build(model, config.optimizer.init(), config.learning_rate)
+    } else {
+        LearnerBuilder::new(ARTIFACT_DIR)
+            .metric_train_numeric(AccuracyMetric::new())
+            .metric_valid_numeric(AccuracyMetric::new())
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .early_stopping(MetricEarlyStoppingStrategy::new::<LossMetric<B>>(
+                Aggregate::Mean,
+                Direction::Lowest,
+                Split::Valid,
+                StoppingCondition::NoImprovementSince {
+                    n_epochs: config.stop_after_n_epochs,
+                },
+            ))
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .summary()
+            .build(model, config.optimizer.init(), config.learning_rate)
+    };
+
+    learner.fit(dataloader_train, dataloader_test)
+}
+
+fn fit_regression<B: AutodiffBackend>(
+    config: &ExpConfig,
+    model: TabularModel<B>,
+    train_dataset: TabularDataset,
+    test_dataset: TabularDataset,
+    device: B::Device,
+) -> TabularModel<B> {
+    let batcher_train = TabularBatcher::<B>::new(device.clone());
+
+    let batcher_test = TabularBatcher::<B::InnerBackend>::new(device.clone());
+
+    let dataloader_train = DataLoaderBuilder::<_, RegressionBatch<B>>::new(batcher_train)
+        .batch_size(config.batch_size)
+        .shuffle(config.seed)
+        .num_workers(config.num_workers)
+        .build(train_dataset);
+
+    let dataloader_test =
+        DataLoaderBuilder::<_, RegressionBatch<B::InnerBackend>>::new(batcher_test)
+            .batch_size(config.batch_size)
+            .shuffle(config.seed)
+            .num_workers(config.num_workers)
+            .build(test_dataset);
+
+    let learner = if cfg!(feature = "cocos") {
+        LearnerBuilder::new(ARTIFACT_DIR)
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .early_stopping(MetricEarlyStoppingStrategy::new::<LossMetric<B>>(
+                Aggregate::Mean,
+                Direction::Lowest,
+                Split::Valid,
+                StoppingCondition::NoImprovementSince {
+                    n_epochs: config.stop_after_n_epochs,
+                },
+            ))
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
+            .build(model, config.optimizer.init(), config.learning_rate)
+    } else {
+        LearnerBuilder::new(ARTIFACT_DIR)
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .early_stopping(MetricEarlyStoppingStrategy::new::<LossMetric<B>>(
+                Aggregate::Mean,
+                Direction::Lowest,
+                Split::Valid,
+                StoppingCondition::NoImprovementSince {
+                    n_epochs: config.stop_after_n_epochs,
+                },
+            ))
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .summary()
+            .build(model, config.optimizer.init(), config.learning_rate)
+    };
+
+    learner.fit(dataloader_train, dataloader_test)
+}
@@ -0,0 +1,18 @@
+use std::{env, fs, path::Path};
+
+/// Embeds the `tokenizer.json` at `EMBEDDED_TOKENIZER_PATH`, if set, so that the model
+/// can tokenize without network access or an uploaded tokenizer.
+fn main() {
+    println!("cargo:rerun-if-env-changed=EMBEDDED_TOKENIZER_PATH");
+
+    let out =
+        Path::new(&env::var("OUT_DIR").expect("OUT_DIR is set by cargo")).join("tokenizer.json");
+    match env::var("EMBEDDED_TOKENIZER_PATH") {
+        Ok(path) => {
+            println!("cargo:rerun-if-changed={}", path);
+            fs::copy(&path, &out)
+                .unwrap_or_else(|e| panic!("Failed to embed tokenizer {}: {}", path, e));
+        }
+        Err(_) => fs::write(&out, []).expect("Failed to write empty tokenizer"),
+    }
+}
@@ -0,0 +1,174 @@
+use std::{path::Path, sync::Arc};
+
+use burn::{
+    data::{dataloader::batcher::Batcher, dataset::Dataset},
+    prelude::*,
+    tensor::{backend::Backend, Tensor},
+};
+use derive_new::new;
+pub use lib::tokenizer::Tokenizer;
+use lib::AlgorithmError;
+use nn::attention::generate_padding_mask;
+
+#[derive(new, Clone, Debug)]
+pub struct ClassificationItem {
+    pub text: String,
+    pub label: usize,
+}
+
+pub trait ClassificationDataset: Dataset<ClassificationItem> {
+    fn num_classes(&self) -> usize;
+    fn class_name(&self, label: usize) -> Result<String, AlgorithmError>;
+}
+
+/// The `tokenizer.json` embedded at build time from `EMBEDDED_TOKENIZER_PATH`, empty when
+/// none was given.
+static EMBEDDED_TOKENIZER: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/tokenizer.json"));
+
+pub struct BertCasedTokenizer {
+    tokenizer: tokenizers::Tokenizer,
+    pad_token: usize,
+}
+
+impl BertCasedTokenizer {
+    /// Loads the tokenizer from `TOKENIZER_PATH`, the uploaded `tokenizer` dataset or the
+    /// one embedded at build time, in that order. Outside cocos it is otherwise downloaded
+    /// from HuggingFace.
+    pub fn load() -> Result<Self, AlgorithmError> {
+        let tokenizer = match lib::datasets::tokenizer_source(EMBEDDED_TOKENIZER)? {
+            Some(lib::datasets::TokenizerSource::File(path)) => {
+                tokenizers::Tokenizer::from_file(&path).map_err(|e| {
+                    AlgorithmError::Tokenizer(format!("Failed to load {}: {}", path.display(), e))
+                })?
+            }
+            Some(lib::datasets::TokenizerSource::Embedded(bytes)) => {
+                tokenizers::Tokenizer::from_bytes(bytes).map_err(|e| {
+                    AlgorithmError::Tokenizer(format!("Failed to load the embedded tokenizer: {}", e))
+                })?
+            }
+            None if !cfg!(feature = "cocos") => {
+                tokenizers::Tokenizer::from_pretrained("bert-base-cased", None).map_err(|e| {
+                    AlgorithmError::Tokenizer(format!("Failed to download bert-base-cased: {}", e))
+                })?
+            }
+            None => {
+                return Err(AlgorithmError::Tokenizer(format!(
+                    "No tokenizer found. Set {}, upload a tokenizer.json dataset or build with EMBEDDED_TOKENIZER_PATH",
+                    lib::datasets::TOKENIZER_PATH_ENV
+                )))
+            }
+        };
+
+        let pad_token = tokenizer.token_to_id("[PAD]").ok_or_else(|| {
+            AlgorithmError::Tokenizer("The tokenizer has no [PAD] token".to_string())
+        })?;
+
+        Ok(Self {
+            tokenizer,
+            pad_token: pad_token as usize,
+        })
+    }
+}
+
+impl BertCasedTokenizer {
+    /// Saves the tokenizer as a `tokenizer.json`, so that inference encodes text exactly
+    /// like the model was trained on.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AlgorithmError> {
+        let path = path.as_ref();
+        self.tokenizer.save(path, false).map_err(|e| {
+            AlgorithmError::Tokenizer(format!("Failed to save {}: {}", path.display(), e))
+        })
+    }
+}
+
+impl Tokenizer for BertCasedTokenizer {
+    fn encode(&self, value: &str) -> Vec<usize> {
+        let tokens = self.tokenizer.encode(value, true).unwrap();
+        tokens.get_ids().iter().map(|t| *t as usize).collect()
+    }
+
+    fn decode(&self, tokens: &[usize]) -> String {
+        let tokens = tokens.iter().map(|t| *t as u32).collect::<Vec<u32>>();
+        self.tokenizer.decode(&tokens, false).unwrap()
+    }
+
+    fn vocab_size(&self) -> usize {
+        self.tokenizer.get_vocab_size(true)
+    }
+
+    fn pad_token(&self) -> usize {
+        self.pad_token
+    }
+}
+
+#[derive(Clone, new)]
+pub struct ClassificationBatcher<B: Backend> {
+    tokenizer: Arc<dyn Tokenizer>,
+    device: B::Device,
+    max_seq_length: usize,
+}
+
+#[derive(Debug, Clone, new)]
+pub struct ClassificationTrainingBatch<B: Backend> {
+    pub tokens: Tensor<B, 2, Int>,
+    pub labels: Tensor<B, 1, Int>,
+    pub mask_pad: Tensor<B, 2, Bool>,
+}
+
+#[derive(Debug, Clone, new)]
+pub struct ClassificationInferenceBatch<B: Backend> {
+    pub tokens: Tensor<B, 2, Int>,
+    pub mask_pad: Tensor<B, 2, Bool>,
+}
+
+impl<B: Backend> Batcher<ClassificationItem, ClassificationTrainingBatch<B>>
+    for ClassificationBatcher<B>
+{
+    fn batch(&self, items: Vec<ClassificationItem>) -> ClassificationTrainingBatch<B> {
+        let mut tokens_list = Vec::with_capacity(items.len());
+        let mut labels_list = Vec::with_capacity(items.len());
+
+        for item in items {
+            tokens_list.push(self.tokenizer.encode(&item.text));
+            labels_list.push(Tensor::from_data(
+                TensorData::from([(item.label as i64).elem::<B::IntElem>()]),
+                &self.device,
+            ));
+        }
+
+        let mask = generate_padding_mask(
+            self.tokenizer.pad_token(),
+            tokens_list,
+            Some(self.max_seq_length),
+            &self.device,
+        );
+
+        ClassificationTrainingBatch {
+            tokens: mask.tensor,
+            labels: Tensor::cat(labels_list, 0),
+            mask_pad: mask.mask,
+        }
+    }
+}
+
+impl<B: Backend> Batcher<String, ClassificationInferenceBatch<B>> for ClassificationBatcher<B> {
+    fn batch(&self, items: Vec<String>) -> ClassificationInferenceBatch<B> {
+        let mut tokens_list = Vec::with_capacity(items.len());
+
+        for item in items {
+            tokens_list.push(self.tokenizer.encode(&item));
+        }
+
+        let mask = generate_padding_mask(
+            self.tokenizer.pad_token(),
+            tokens_list,
+            Some(self.max_seq_length),
+            &B::Device::default(),
+        );
+
+        ClassificationInferenceBatch {
+            tokens: mask.tensor.to_device(&self.device),
+            mask_pad: mask.mask.to_device(&self.device),
+        }
+    }
+}
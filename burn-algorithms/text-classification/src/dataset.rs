@@ -0,0 +1,287 @@
+use std::path::Path;
+
+use burn::{
+    config::Config,
+    data::dataset::{transform::ShuffledDataset, Dataset, InMemDataset},
+};
+use lib::AlgorithmError;
+
+use crate::data::{ClassificationDataset, ClassificationItem};
+
+/// Describes a text classification dataset stored as CSV files with a header row.
+///
+/// The text of an item is made of its `text_columns`, joined with a space. The value in
+/// `label_column` must be one of `labels`, and the class of an item is the position of
+/// its label in that list. `class_names` gives readable names to the classes and
+/// defaults to the labels themselves.
+#[derive(Config, Debug)]
+pub struct CsvDatasetConfig {
+    pub text_columns: Vec<String>,
+    pub label_column: String,
+    pub labels: Vec<String>,
+    #[config(default = "Vec::new()")]
+    pub class_names: Vec<String>,
+    /// Train and test CSV files, found by [`lib::datasets::train_test_files`]. Without a
+    /// test file, the shuffled train file is split between both sets with `train_ratio`.
+    pub train_file: String,
+    #[config(default = "None")]
+    pub test_file: Option<String>,
+    #[config(default = 0.8)]
+    pub train_ratio: f64,
+    #[config(default = "','")]
+    pub delimiter: char,
+}
+
+/// Text classification items read from a CSV file as described by a [`CsvDatasetConfig`].
+pub struct CsvDataset {
+    dataset: InMemDataset<ClassificationItem>,
+    class_names: Vec<String>,
+}
+
+impl Dataset<ClassificationItem> for CsvDataset {
+    fn get(&self, index: usize) -> Option<ClassificationItem> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+impl ClassificationDataset for CsvDataset {
+    fn num_classes(&self) -> usize {
+        self.class_names.len()
+    }
+
+    fn class_name(&self, label: usize) -> Result<String, AlgorithmError> {
+        self.class_names
+            .get(label)
+            .cloned()
+            .ok_or_else(|| AlgorithmError::InvalidLabel(label.to_string()))
+    }
+}
+
+impl CsvDatasetConfig {
+    /// Names of the classes, in label order.
+    pub fn class_names(&self) -> Result<Vec<String>, AlgorithmError> {
+        if self.labels.is_empty() {
+            return Err(AlgorithmError::InvalidConfig(
+                "dataset.labels must list the values of the label column".to_string(),
+            ));
+        }
+
+        match self.class_names.len() {
+            0 => Ok(self.labels.clone()),
+            len if len == self.labels.len() => Ok(self.class_names.clone()),
+            len => Err(AlgorithmError::InvalidConfig(format!(
+                "dataset.class_names has {} names for {} labels",
+                len,
+                self.labels.len()
+            ))),
+        }
+    }
+
+    /// Share of the train file used for training when there is no test file, strictly
+    /// between 0 and 1.
+    pub fn train_ratio(&self) -> Result<f64, AlgorithmError> {
+        match self.train_ratio {
+            ratio if ratio > 0.0 && ratio < 1.0 => Ok(ratio),
+            ratio => Err(AlgorithmError::InvalidConfig(format!(
+                "dataset.train_ratio {} is not between 0 and 1",
+                ratio
+            ))),
+        }
+    }
+}
+
+impl CsvDataset {
+    /// Reads every record of `path`, checking the columns and labels up front since the
+    /// batcher cannot fail.
+    pub fn new(path: &Path, config: &CsvDatasetConfig) -> Result<Self, AlgorithmError> {
+        let class_names = config.class_names()?;
+        let (headers, records) = lib::datasets::read_csv_records(path, config.delimiter)?;
+        let column = |name: &str| {
+            headers.iter().position(|h| h == name).ok_or_else(|| {
+                AlgorithmError::InvalidDataset(format!(
+                    "{} has no column {:?}",
+                    path.display(),
+                    name
+                ))
+            })
+        };
+        let text_columns = config
+            .text_columns
+            .iter()
+            .map(|name| column(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let label_column = column(&config.label_column)?;
+
+        let mut items = Vec::new();
+        for record in records {
+            let label = record.get(label_column).unwrap_or_default();
+            let label = config
+                .labels
+                .iter()
+                .position(|l| l == label)
+                .ok_or_else(|| AlgorithmError::InvalidLabel(label.to_string()))?;
+            let text = text_columns
+                .iter()
+                .filter_map(|column| record.get(*column))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            items.push(ClassificationItem::new(text, label));
+        }
+
+        Ok(Self {
+            dataset: InMemDataset::new(items),
+            class_names,
+        })
+    }
+
+    /// Reads the train and test datasets. They come from the uploaded datasets when
+    /// `data_dir` is `None`, and from files in `data_dir` otherwise.
+    pub fn splits(
+        config: &CsvDatasetConfig,
+        data_dir: Option<&Path>,
+    ) -> Result<(Self, Self), AlgorithmError> {
+        let train_ratio = config.train_ratio()?;
+        let (train_file, test_file) = lib::datasets::train_test_files(
+            &config.train_file,
+            config.test_file.as_deref(),
+            data_dir,
+        )?;
+        let train = Self::new(&train_file, config)?;
+
+        match test_file {
+            Some(test_file) => Ok((train, Self::new(&test_file, config)?)),
+            None => Ok(train.split(train_ratio)),
+        }
+    }
+
+    /// Shuffles the items with a fixed seed and splits them between a train and a test
+    /// dataset, with a `train_ratio` checked by [`CsvDatasetConfig::train_ratio`].
+    fn split(self, train_ratio: f64) -> (Self, Self) {
+        let len = self.dataset.len();
+        let at = (len as f64 * train_ratio) as usize;
+        let items = ShuffledDataset::with_seed(self.dataset, 42)
+            .iter()
+            .collect::<Vec<_>>();
+        let (train, test) = items.split_at(at);
+
+        (
+            Self {
+                dataset: InMemDataset::new(train.to_vec()),
+                class_names: self.class_names.clone(),
+            },
+            Self {
+                dataset: InMemDataset::new(test.to_vec()),
+                class_names: self.class_names,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::PathBuf};
+
+    const CSV: &str = "title,body,sentiment\nGreat,loved it,pos\nBad,hated it,neg\nFine,it was ok,pos\nMeh,not again,neg\n";
+
+    fn config() -> CsvDatasetConfig {
+        CsvDatasetConfig::new(
+            vec!["title".to_string(), "body".to_string()],
+            "sentiment".to_string(),
+            vec!["neg".to_string(), "pos".to_string()],
+            "reviews.csv".to_string(),
+        )
+    }
+
+    fn data_dir(name: &str, csv: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "text-classification-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("reviews.csv"), csv).unwrap();
+        dir
+    }
+
+    #[test]
+    fn labels_are_mapped_to_their_index() {
+        let dir = data_dir("labels", CSV);
+        let config =
+            config().with_class_names(vec!["negative".to_string(), "positive".to_string()]);
+
+        let dataset = CsvDataset::new(&dir.join("reviews.csv"), &config).unwrap();
+        let items = dataset.iter().collect::<Vec<_>>();
+        assert_eq!(
+            items.iter().map(|item| item.label).collect::<Vec<_>>(),
+            vec![1, 0, 1, 0]
+        );
+        assert_eq!(items[0].text, "Great loved it");
+        assert_eq!(dataset.num_classes(), 2);
+        assert_eq!(dataset.class_name(1).unwrap(), "positive");
+        assert!(dataset.class_name(2).is_err());
+
+        let (train, test) = CsvDataset::splits(&config.with_train_ratio(0.5), Some(&dir)).unwrap();
+        assert_eq!((train.len(), test.len()), (2, 2));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_column_and_unknown_label_are_rejected() {
+        let dir = data_dir("invalid", CSV);
+        let path = dir.join("reviews.csv");
+
+        let err = CsvDataset::new(
+            &path,
+            &CsvDatasetConfig {
+                label_column: "rating".to_string(),
+                ..config()
+            },
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), "invalid_dataset");
+        assert!(err.to_string().contains("rating"));
+
+        let err = CsvDataset::new(
+            &path,
+            &CsvDatasetConfig {
+                labels: vec!["pos".to_string()],
+                ..config()
+            },
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), "invalid_label");
+        assert!(err.to_string().contains("neg"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        let config = config();
+        let err = config
+            .clone()
+            .with_class_names(vec!["positive".to_string()])
+            .class_names()
+            .unwrap_err();
+        assert_eq!(err.kind(), "invalid_config");
+
+        for ratio in [0.0, 1.0, 1.5, -0.2] {
+            let err = config
+                .clone()
+                .with_train_ratio(ratio)
+                .train_ratio()
+                .unwrap_err();
+            assert_eq!(err.kind(), "invalid_config");
+        }
+        assert_eq!(config.train_ratio().unwrap(), 0.8);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod data;
+pub mod dataset;
+pub mod model;
+pub mod training;
@@ -0,0 +1,50 @@
+// The model is defined in lib, where the inference of the text models loads it from, so
+// training only adds the loss on top of its output
+
+use burn::{
+    nn::loss::CrossEntropyLossConfig,
+    tensor::backend::{AutodiffBackend, Backend},
+    train::{ClassificationOutput, TrainOutput, TrainStep, ValidStep},
+};
+use lib::text_model::TextClassificationModel;
+
+use crate::data::ClassificationTrainingBatch;
+
+/// Runs `model` on a training batch and computes the cross entropy loss against its
+/// labels.
+pub fn forward_classification<B: Backend>(
+    model: &TextClassificationModel<B>,
+    item: ClassificationTrainingBatch<B>,
+) -> ClassificationOutput<B> {
+    let output = model.forward(item.tokens, item.mask_pad);
+    let labels = item.labels.to_device(&output.device());
+
+    let loss = CrossEntropyLossConfig::new()
+        .init(&output.device())
+        .forward(output.clone(), labels.clone());
+
+    ClassificationOutput {
+        loss,
+        output,
+        targets: labels,
+    }
+}
+
+impl<B: AutodiffBackend> TrainStep<ClassificationTrainingBatch<B>, ClassificationOutput<B>>
+    for TextClassificationModel<B>
+{
+    fn step(&self, item: ClassificationTrainingBatch<B>) -> TrainOutput<ClassificationOutput<B>> {
+        let item = forward_classification(self, item);
+        let grads = item.loss.backward();
+
+        TrainOutput::new(self, grads, item)
+    }
+}
+
+impl<B: Backend> ValidStep<ClassificationTrainingBatch<B>, ClassificationOutput<B>>
+    for TextClassificationModel<B>
+{
+    fn step(&self, item: ClassificationTrainingBatch<B>) -> ClassificationOutput<B> {
+        forward_classification(self, item)
+    }
+}
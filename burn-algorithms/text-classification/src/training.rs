@@ -0,0 +1,202 @@
+use crate::{
+    data::{BertCasedTokenizer, ClassificationBatcher, ClassificationDataset, Tokenizer},
+    dataset::{CsvDataset, CsvDatasetConfig},
+};
+use burn::{
+    data::{dataloader::DataLoaderBuilder, dataset::transform::SamplerDataset},
+    lr_scheduler::noam::NoamLrSchedulerConfig,
+    nn::transformer::TransformerEncoderConfig,
+    optim::{decay::WeightDecayConfig, AdamConfig},
+    prelude::*,
+    record::{CompactRecorder, HalfPrecisionSettings, NamedMpkBytesRecorder, Recorder},
+    tensor::backend::AutodiffBackend,
+    train::{
+        metric::{AccuracyMetric, LearningRateMetric, LossMetric},
+        LearnerBuilder,
+    },
+};
+use lib::{text_model::TextClassificationModel, tokenizer::TOKENIZER_FILE, AlgorithmError};
+use std::{path::Path, sync::Arc};
+
+#[cfg(feature = "cocos")]
+static ARTIFACT_DIR: &str = "results";
+
+#[cfg(not(feature = "cocos"))]
+static ARTIFACT_DIR: &str = "artifacts/text-classification/";
+
+#[derive(Config)]
+pub struct ExperimentConfig {
+    pub dataset: CsvDatasetConfig,
+    pub transformer: TransformerEncoderConfig,
+    pub optimizer: AdamConfig,
+
+    #[config(default = 512)]
+    pub max_seq_length: usize,
+    #[config(default = 32)]
+    pub batch_size: usize,
+    #[config(default = 5)]
+    pub num_epochs: usize,
+    #[config(default = 1)]
+    pub num_workers: usize,
+    #[config(default = 1e-2)]
+    pub learning_rate: f64,
+}
+
+impl ExperimentConfig {
+    /// The transformer and optimizer shared by the text classification models, for
+    /// `dataset`.
+    pub fn with_defaults(dataset: CsvDatasetConfig) -> Self {
+        Self::new(
+            dataset,
+            TransformerEncoderConfig::new(256, 1024, 8, 4)
+                .with_norm_first(true)
+                .with_quiet_softmax(true),
+            AdamConfig::new().with_weight_decay(Some(WeightDecayConfig::new(5e-5))),
+        )
+    }
+}
+
+pub fn train<B: AutodiffBackend, D: ClassificationDataset + 'static>(
+    device: B::Device,
+    dataset_train: D,
+    dataset_test: D,
+    config: ExperimentConfig,
+    artifact_dir: &str,
+) -> Result<(), AlgorithmError> {
+    let tokenizer = Arc::new(BertCasedTokenizer::load()?);
+
+    let batcher_train =
+        ClassificationBatcher::<B>::new(tokenizer.clone(), device.clone(), config.max_seq_length);
+    let batcher_test = ClassificationBatcher::<B::InnerBackend>::new(
+        tokenizer.clone(),
+        device.clone(),
+        config.max_seq_length,
+    );
+
+    let model = TextClassificationModel::init(
+        &config.transformer,
+        dataset_train.num_classes(),
+        tokenizer.vocab_size(),
+        config.max_seq_length,
+        &device,
+    );
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &NamedMpkBytesRecorder::<HalfPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
+
+    let dataloader_train = DataLoaderBuilder::new(batcher_train)
+        .batch_size(config.batch_size)
+        .num_workers(config.num_workers)
+        .build(SamplerDataset::new(dataset_train, 50_000));
+    let dataloader_test = DataLoaderBuilder::new(batcher_test)
+        .batch_size(config.batch_size)
+        .num_workers(config.num_workers)
+        .build(SamplerDataset::new(dataset_test, 5_000));
+
+    let optim = config.optimizer.init();
+
+    let lr_scheduler = NoamLrSchedulerConfig::new(config.learning_rate)
+        .with_warmup_steps(1000)
+        .with_model_size(config.transformer.d_model)
+        .init()
+        .unwrap();
+
+    let learner = if cfg!(feature = "cocos") {
+        LearnerBuilder::new(artifact_dir)
+            .metric_train_numeric(AccuracyMetric::new())
+            .metric_valid_numeric(AccuracyMetric::new())
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .metric_train_numeric(LearningRateMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .renderer(lib::cocos_metrics_renderer(artifact_dir))
+            .build(model, optim, lr_scheduler)
+    } else {
+        LearnerBuilder::new(artifact_dir)
+            .metric_train_numeric(AccuracyMetric::new())
+            .metric_valid_numeric(AccuracyMetric::new())
+            .metric_train_numeric(LossMetric::new())
+            .metric_valid_numeric(LossMetric::new())
+            .metric_train_numeric(LearningRateMetric::new())
+            .with_file_checkpointer(CompactRecorder::new())
+            .devices(vec![device.clone()])
+            .num_epochs(config.num_epochs)
+            .summary()
+            .build(model, optim, lr_scheduler)
+    };
+
+    let model_trained = learner.fit(dataloader_train, dataloader_test);
+
+    config
+        .save(format!("{artifact_dir}/config.json"))
+        .expect("Config should be saved successfully");
+    tokenizer.save(Path::new(artifact_dir).join(TOKENIZER_FILE))?;
+    CompactRecorder::new()
+        .record(
+            model_trained.into_record(),
+            format!("{artifact_dir}/model").into(),
+        )
+        .expect("Model should be saved successfully");
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(artifact_dir).expect("Failed to write results manifest");
+    }
+
+    Ok(())
+}
+
+/// Trains on the CSV dataset described by the `dataset` field of the experiment config,
+/// starting from `default`. The CSV files are read from `data_dir`, or from the uploaded
+/// datasets when it is `None`. Errors are recorded in `artifact_dir` before exiting.
+pub fn run_csv<B: AutodiffBackend>(
+    device: B::Device,
+    default: ExperimentConfig,
+    data_dir: Option<&Path>,
+    artifact_dir: &str,
+) {
+    if let Err(err) = launch::<B>(device, default, data_dir, artifact_dir) {
+        lib::exit_with_error(artifact_dir, err);
+    }
+}
+
+/// Trains on a CSV dataset uploaded under cocos or placed in `datasets`, configured
+/// entirely through the experiment config.
+pub fn run<B: AutodiffBackend>(device: B::Device) {
+    let dataset = CsvDatasetConfig::new(
+        vec!["text".to_string()],
+        "label".to_string(),
+        Vec::new(),
+        "train.csv".to_string(),
+    );
+
+    run_csv::<B>(
+        device,
+        ExperimentConfig::with_defaults(dataset),
+        None,
+        ARTIFACT_DIR,
+    );
+}
+
+fn launch<B: AutodiffBackend>(
+    device: B::Device,
+    default: ExperimentConfig,
+    data_dir: Option<&Path>,
+    artifact_dir: &str,
+) -> Result<(), AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(artifact_dir)?;
+    }
+
+    let config = lib::load_config(default)?;
+    let (dataset_train, dataset_test) = CsvDataset::splits(&config.dataset, data_dir)?;
+
+    train::<B, CsvDataset>(device, dataset_train, dataset_test, config, artifact_dir)
+}
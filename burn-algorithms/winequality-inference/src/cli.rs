@@ -0,0 +1,78 @@
+// The command line of the winequality inference, run by `winequality-inference` and by
+// `burn-algorithms infer winequality`
+
+use crate::infer::{batch_inference, inference, WineQualityItem};
+use futures::executor;
+use lib::{results::RESULTS_DIR, AlgorithmError, OutputFormat};
+use std::path::PathBuf;
+
+static EXAMPLE: &str = r#"{"fixed_acidity": 7,"volatile_acidity": 0.27,"citric_acid": 0.36,"residual_sugar": 20.7,"chlorides": 0.045,"free_sulfur_dioxide": 45,"total_sulfur_dioxide": 170,"density": 1.001,"ph": 3,"sulphates": 0.45,"alcohol": 8.8}"#;
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} [--model <path>] <JSON input>", program);
+    eprintln!(
+        "       {} [--model <path>] [--format json|csv] --batch [<JSON array> | <CSV or JSON file>]",
+        program
+    );
+    std::process::exit(1);
+}
+
+/// Parses `args`, the arguments after `program`, runs a single or a batch inference and
+/// prints the result, or saves it under cocos. Exits with the code of the error when the
+/// inference fails.
+pub fn run(program: &str, args: &[String]) {
+    let mut model_path = None;
+    let mut format = OutputFormat::default();
+    let mut batch = false;
+    let mut input = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--model" => match rest.next() {
+                Some(path) => model_path = Some(PathBuf::from(path)),
+                None => usage(program),
+            },
+            "--format" => match rest.next().map(|format| format.parse()) {
+                Some(Ok(value)) => format = value,
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+                None => usage(program),
+            },
+            "--batch" => batch = true,
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => usage(program),
+        }
+    }
+
+    let result = match (batch, input) {
+        (true, input) => lib::read_batch::<WineQualityItem>(input)
+            .and_then(|items| executor::block_on(batch_inference(&items, model_path)))
+            .map(|qualities| lib::write_predictions(&qualities, format)),
+        (false, Some(input)) => lib::read_input(input, EXAMPLE)
+            .and_then(|item| executor::block_on(inference(item, model_path)))
+            .map(|quality| quality.to_string()),
+        (false, None) => usage(program),
+    };
+    output(result);
+}
+
+fn output(result: Result<String, AlgorithmError>) {
+    match result {
+        Ok(result) => {
+            if cfg!(feature = "cocos") {
+                match lib::save_results(result) {
+                    Ok(_) => (),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{:}", result.trim_end());
+            }
+        }
+        Err(e) => lib::exit_with_error(RESULTS_DIR, e),
+    };
+}
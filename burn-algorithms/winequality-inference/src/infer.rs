@@ -1,55 +1,137 @@
-use crate::model::{build_and_load_model, NDBackend};
+use crate::model::{build_and_load_model, model_source, NDBackend};
 use burn::tensor::Tensor;
-use serde::Deserialize;
+use lib::{
+    preprocessing::WINE_QUALITY_FEATURES, scaler::SCALER_FILE, AlgorithmError, FeatureScaler,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-#[derive(Deserialize, Debug)]
+static SCALER_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/scaler.json"));
+
+/// A wine to score. Features are read from JSON by field name, or from a CSV file by the
+/// column names of the Wine Quality dataset.
+#[derive(Serialize, Deserialize, Debug)]
 pub struct WineQualityItem {
+    #[serde(rename = "fixed acidity", alias = "fixed_acidity")]
     pub fixed_acidity: f64,
+    #[serde(rename = "volatile acidity", alias = "volatile_acidity")]
     pub volatile_acidity: f64,
+    #[serde(rename = "citric acid", alias = "citric_acid")]
     pub citric_acid: f64,
+    #[serde(rename = "residual sugar", alias = "residual_sugar")]
     pub residual_sugar: f64,
     pub chlorides: f64,
+    #[serde(rename = "free sulfur dioxide", alias = "free_sulfur_dioxide")]
     pub free_sulfur_dioxide: f64,
+    #[serde(rename = "total sulfur dioxide", alias = "total_sulfur_dioxide")]
     pub total_sulfur_dioxide: f64,
     pub density: f64,
+    #[serde(rename = "pH", alias = "ph")]
     pub ph: f64,
     pub sulphates: f64,
     pub alcohol: f64,
 }
 
-pub async fn inference(wine_quality_item: WineQualityItem) -> Result<f32, String> {
-    let model = match build_and_load_model() {
-        Ok(model) => Some(model),
-        Err(e) => return Err(e),
-    };
+impl WineQualityItem {
+    /// The input features, in the [`WINE_QUALITY_FEATURES`] order the model was trained on.
+    pub fn features(&self) -> [f64; 11] {
+        [
+            self.fixed_acidity,
+            self.volatile_acidity,
+            self.citric_acid,
+            self.residual_sugar,
+            self.chlorides,
+            self.free_sulfur_dioxide,
+            self.total_sulfur_dioxide,
+            self.density,
+            self.ph,
+            self.sulphates,
+            self.alcohol,
+        ]
+    }
+}
+
+/// Predicts the quality of `wine_quality_item` with the model found by [`model_source`]
+/// from `model_path`, and the feature scaler saved next to it.
+pub async fn inference(
+    wine_quality_item: WineQualityItem,
+    model_path: Option<PathBuf>,
+) -> Result<f32, AlgorithmError> {
+    let qualities = batch_inference(&[wine_quality_item], model_path).await?;
+
+    Ok(qualities[0])
+}
+
+/// Predicts the quality of every item of a batch, in order, with a single forward pass of
+/// the model found by [`model_source`] from `model_path`.
+pub async fn batch_inference(
+    wine_quality_items: &[WineQualityItem],
+    model_path: Option<PathBuf>,
+) -> Result<Vec<f32>, AlgorithmError> {
+    if wine_quality_items.is_empty() {
+        return Err(AlgorithmError::InvalidDataset(
+            "The batch has no items".to_string(),
+        ));
+    }
+
+    let source = model_source(model_path)?;
+    let model = build_and_load_model(&source)?;
 
-    let model = match model.as_ref() {
-        Some(model) => model,
-        None => return Err("Model not found".to_string()),
-    };
+    let scaler = source
+        .sibling(SCALER_FILE, SCALER_ENCODED)
+        .and_then(|bytes| FeatureScaler::from_slice(&bytes))?;
+    if scaler.len() != WINE_QUALITY_FEATURES.len() {
+        return Err(AlgorithmError::InvalidConfig(format!(
+            "The feature scaler expects {} features instead of {}",
+            scaler.len(),
+            WINE_QUALITY_FEATURES.len()
+        )));
+    }
 
     let device = Default::default();
 
-    // The constants used to normalize the input data are the same as the ones used to train the model
-    // They are the minimum and maximum values of each feature in the training dataset
-    let input: [f32; 11] = [
-        (wine_quality_item.fixed_acidity as f32 - 3.8) / (14.2 - 3.8),
-        (wine_quality_item.volatile_acidity as f32 - 0.08) / (1.1 - 0.08),
-        (wine_quality_item.citric_acid as f32 - 0.0) / (1.66 - 0.0),
-        (wine_quality_item.residual_sugar as f32 - 0.6) / (65.8 - 0.6),
-        (wine_quality_item.chlorides as f32 - 0.009) / (0.346 - 0.009),
-        (wine_quality_item.free_sulfur_dioxide as f32 - 2.0) / (289.0 - 2.0),
-        (wine_quality_item.total_sulfur_dioxide as f32 - 9.0) / (440.0 - 9.0),
-        (wine_quality_item.density as f32 - 0.98711) / (1.03898 - 0.98711),
-        (wine_quality_item.ph as f32 - 2.72) / (3.82 - 2.72),
-        (wine_quality_item.sulphates as f32 - 0.22) / (1.08 - 0.22),
-        (wine_quality_item.alcohol as f32 - 8.0) / (14.2 - 8.0),
-    ];
-
-    let input = Tensor::<NDBackend, 1>::from_floats(input, &device).unsqueeze();
+    // The inputs are normalized with the scaler fitted on the training split, saved next to
+    // the model by the training algorithm
+    let input = wine_quality_items
+        .iter()
+        .flat_map(|item| scaler.transform(&item.features()))
+        .collect::<Vec<_>>();
+
+    let input = Tensor::<NDBackend, 1>::from_floats(input.as_slice(), &device)
+        .reshape([wine_quality_items.len(), WINE_QUALITY_FEATURES.len()]);
     let output = model.forward(input);
 
-    let result = output.into_scalar();
+    let result = output.into_data().iter::<f32>().collect();
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_and_csv_items_have_the_same_features() {
+        let json = r#"{"fixed_acidity": 7,"volatile_acidity": 0.27,"citric_acid": 0.36,"residual_sugar": 20.7,"chlorides": 0.045,"free_sulfur_dioxide": 45,"total_sulfur_dioxide": 170,"density": 1.001,"ph": 3,"sulphates": 0.45,"alcohol": 8.8}"#;
+        let item: WineQualityItem = serde_json::from_str(json).unwrap();
+
+        let dir =
+            std::env::temp_dir().join(format!("winequality-inference-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("wines.csv");
+        std::fs::write(
+            &csv,
+            "\"fixed acidity\";\"volatile acidity\";\"citric acid\";\"residual sugar\";\"chlorides\";\"free sulfur dioxide\";\"total sulfur dioxide\";\"density\";\"pH\";\"sulphates\";\"alcohol\";\"quality\"\n7;0.27;0.36;20.7;0.045;45;170;1.001;3;0.45;8.8;6\n",
+        )
+        .unwrap();
+        let items = lib::read_batch::<WineQualityItem>(csv.to_str()).unwrap();
+
+        assert_eq!(
+            item.features(),
+            [7.0, 0.27, 0.36, 20.7, 0.045, 45.0, 170.0, 1.001, 3.0, 0.45, 8.8]
+        );
+        assert_eq!(items[0].features(), item.features());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -4,12 +4,47 @@ use burn::{
     module::Module,
     nn::{Linear, LinearConfig},
     prelude::*,
-    record::{BinBytesRecorder, FullPrecisionSettings, Recorder},
 };
+use lib::{
+    config::CONFIG_FILE, preprocessing::WINE_QUALITY_FEATURES, weights::load_model, AlgorithmError,
+    ModelSource,
+};
+use serde::Deserialize;
+use std::path::PathBuf;
 
 pub type NDBackend = burn::backend::ndarray::NdArray<f32>;
 
-static STATE_ENCODED: &[u8] = include_bytes!("../../artifacts/winequality/model.bin");
+static STATE_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/model.bin"));
+static CONFIG_ENCODED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/config.json"));
+
+/// Number of features of an inference input.
+const INPUT_FEATURES: usize = WINE_QUALITY_FEATURES.len();
+
+/// The fields of the training config that shape the model. The other fields saved with
+/// it are ignored.
+#[derive(Deserialize, Debug)]
+pub struct ModelConfig {
+    pub input_feature_len: usize,
+}
+
+impl ModelConfig {
+    /// Reads the training config saved next to the model in `source`.
+    pub fn load(source: &ModelSource) -> Result<Self, AlgorithmError> {
+        let bytes = source.sibling(CONFIG_FILE, CONFIG_ENCODED)?;
+        let config: Self = serde_json::from_slice(&bytes).map_err(|e| {
+            AlgorithmError::InvalidConfig(format!("Invalid training {}: {}", CONFIG_FILE, e))
+        })?;
+
+        if config.input_feature_len != INPUT_FEATURES {
+            return Err(AlgorithmError::InvalidConfig(format!(
+                "The model was trained on {} features but Wine Quality inputs have {}",
+                config.input_feature_len, INPUT_FEATURES
+            )));
+        }
+
+        Ok(config)
+    }
+}
 
 #[derive(Module, Debug)]
 pub struct Model<B: Backend> {
@@ -17,8 +52,11 @@ pub struct Model<B: Backend> {
 }
 
 impl<B: Backend> Model<B> {
-    pub fn new(device: &B::Device) -> Self {
-        let input_layer = LinearConfig::new(11, 1).with_bias(true).init(device);
+    /// Builds the architecture the model was trained with.
+    pub fn new(config: &ModelConfig, device: &B::Device) -> Self {
+        let input_layer = LinearConfig::new(config.input_feature_len, 1)
+            .with_bias(true)
+            .init(device);
 
         Model { input_layer }
     }
@@ -29,14 +67,21 @@ impl<B: Backend> Model<B> {
     }
 }
 
-pub fn build_and_load_model() -> Result<Model<NDBackend>, String> {
-    let model: Model<NDBackend> = Model::new(&Default::default());
-    let record = match BinBytesRecorder::<FullPrecisionSettings>::default()
-        .load(STATE_ENCODED.to_vec(), &Default::default())
-    {
-        Ok(record) => record,
-        Err(e) => return Err(format!("Failed to load model: {}", e)),
-    };
+/// Finds the model at `path`, in the datasets directory, or embedded in the binary.
+pub fn model_source(path: Option<PathBuf>) -> Result<ModelSource, AlgorithmError> {
+    ModelSource::resolve(path, STATE_ENCODED)
+}
+
+/// Builds the model described by the training config saved next to it and loads it.
+pub fn build_and_load_model(source: &ModelSource) -> Result<Model<NDBackend>, AlgorithmError> {
+    let config = ModelConfig::load(source)?;
+    let model: Model<NDBackend> = Model::new(&config, &Default::default());
 
-    Ok(model.load_record(record))
+    load_model(model, source, &Default::default()).map_err(|e| match e {
+        AlgorithmError::InvalidConfig(message) => AlgorithmError::InvalidConfig(format!(
+            "The model does not match the architecture in {}: {}",
+            CONFIG_FILE, message
+        )),
+        e => e,
+    })
 }
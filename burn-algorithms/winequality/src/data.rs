@@ -8,7 +8,7 @@ use burn::{
     },
     prelude::*,
 };
-use dircpy::copy_dir;
+use lib::{AlgorithmError, FeatureScaler};
 use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -50,6 +50,26 @@ pub struct WineQualityItem {
     pub quality: i64,
 }
 
+impl WineQualityItem {
+    /// The input features, in the [`lib::preprocessing::WINE_QUALITY_FEATURES`] order the
+    /// model expects them.
+    pub fn features(&self) -> [f64; 11] {
+        [
+            self.fixed_acidity,
+            self.volatile_acidity,
+            self.citric_acid,
+            self.residual_sugar,
+            self.chlorides,
+            self.free_sulfur_dioxide,
+            self.total_sulfur_dioxide,
+            self.density,
+            self.ph,
+            self.sulphates,
+            self.alcohol,
+        ]
+    }
+}
+
 type ShuffledData = ShuffledDataset<InMemDataset<WineQualityItem>, WineQualityItem>;
 type PartialData = PartialDataset<ShuffledData, WineQualityItem>;
 
@@ -68,56 +88,45 @@ impl Dataset<WineQualityItem> for WineQualityDataset {
 }
 
 impl WineQualityDataset {
-    pub fn train(path: &PathBuf) -> Self {
-        Self::new("train", path)
+    pub fn train() -> Result<Self, AlgorithmError> {
+        Self::new("train")
     }
 
-    pub fn test(path: &PathBuf) -> Self {
-        Self::new("test", path)
+    pub fn test() -> Result<Self, AlgorithmError> {
+        Self::new("test")
     }
 
-    pub fn new(split: &str, path: &PathBuf) -> Self {
+    pub fn new(split: &str) -> Result<Self, AlgorithmError> {
+        let (path, shared) = Self::read(split)?;
+
         let mut rdr = csv::ReaderBuilder::new();
         let rdr = rdr.delimiter(b';');
 
-        let dataset = InMemDataset::from_csv(path, rdr).unwrap();
+        let dataset =
+            InMemDataset::from_csv(&path, rdr).map_err(|e| AlgorithmError::from_csv(&path, e))?;
 
         let len = dataset.len();
 
         let dataset = ShuffledDataset::with_seed(dataset, 42);
 
-        // The dataset from HuggingFace has only train split, so we manually split the train dataset into train
-        // and test in a 80-20 ratio
-        let filtered_dataset = match split {
-            "train" => PartialData::new(dataset, 0, len * 8 / 10),
-            "test" => PartialData::new(dataset, len * 8 / 10, len),
+        // The dataset from HuggingFace has only train split, so unless a separate test dataset
+        // was uploaded we manually split the train dataset into train and test in a 80-20 ratio
+        let filtered_dataset = match (split, shared) {
+            (_, false) => PartialData::new(dataset, 0, len),
+            ("train", true) => PartialData::new(dataset, 0, len * 8 / 10),
+            ("test", true) => PartialData::new(dataset, len * 8 / 10, len),
             _ => panic!("Invalid split type"),
         };
 
-        Self {
+        Ok(Self {
             dataset: filtered_dataset,
-        }
+        })
     }
 
-    pub fn read() -> PathBuf {
-        let csv_file = if cfg!(feature = "cocos") {
-            let wine_dir = Path::new("datasets");
-            let files = std::fs::read_dir(wine_dir).expect("Failed to read directory");
-            let zipped_file_without_ext = files
-                .map(|f| f.expect("Failed to read file").path())
-                .next()
-                .expect("No file found in the directory");
-            let zipped_file = zipped_file_without_ext.with_extension("zip");
-            std::fs::copy(zipped_file_without_ext.as_path(), &zipped_file)
-                .expect("Failed to copy file");
-            std::fs::remove_file(zipped_file_without_ext.as_path()).expect("Failed to remove file");
-            simple_zip::zip::Decompress::local_buffer(&zipped_file);
-            let src = wine_dir
-                .parent()
-                .expect("Failed to get parent")
-                .join("data");
-            copy_dir(src, wine_dir).expect("Failed to copy directory");
-            wine_dir.join("winequality-white.csv")
+    pub fn read(split: &str) -> Result<(PathBuf, bool), AlgorithmError> {
+        let (csv_file, shared) = if cfg!(feature = "cocos") {
+            lib::dataset_files()
+                .and_then(|files| files.split_file(split, "winequality-white.csv"))?
         } else {
             let example_dir = Path::new(file!())
                 .parent()
@@ -126,18 +135,20 @@ impl WineQualityDataset {
                 .expect("Failed to get parent");
             let wine_dir = example_dir.join("data/");
 
-            wine_dir.join("winequality-white.csv")
+            (wine_dir.join("winequality-white.csv"), true)
         };
         if !csv_file.exists() {
-            panic!("Download the Wine Quality dataset from https://archive.ics.uci.edu/dataset/186/wine+quality and place it in the datasets directory");
+            eprintln!("Download the Wine Quality dataset from https://archive.ics.uci.edu/dataset/186/wine+quality and place it in the datasets directory");
+            return Err(AlgorithmError::MissingFile(csv_file));
         }
-        csv_file
+        Ok((csv_file, shared))
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct WineQualityBatcher<B: Backend> {
     device: B::Device,
+    scaler: FeatureScaler,
 }
 
 #[derive(Clone, Debug)]
@@ -147,8 +158,10 @@ pub struct WineQualityBatch<B: Backend> {
 }
 
 impl<B: Backend> WineQualityBatcher<B> {
-    pub fn new(device: B::Device) -> Self {
-        Self { device }
+    /// Creates a batcher normalizing the features with `scaler`, which is fitted on the
+    /// train split and saved with the model.
+    pub fn new(device: B::Device, scaler: FeatureScaler) -> Self {
+        Self { device, scaler }
     }
 }
 
@@ -156,22 +169,9 @@ impl<B: Backend> Batcher<WineQualityItem, WineQualityBatch<B>> for WineQualityBa
     fn batch(&self, items: Vec<WineQualityItem>) -> WineQualityBatch<B> {
         let mut inputs: Vec<Tensor<B, 2>> = Vec::new();
 
-        // The constants are the min and max values of the dataset
         for item in items.iter() {
             let input_tensor = Tensor::<B, 1>::from_floats(
-                [
-                    (item.fixed_acidity as f32 - 3.8) / (14.2 - 3.8),
-                    (item.volatile_acidity as f32 - 0.08) / (1.1 - 0.08),
-                    (item.citric_acid as f32 - 0.0) / (1.66 - 0.0),
-                    (item.residual_sugar as f32 - 0.6) / (65.8 - 0.6),
-                    (item.chlorides as f32 - 0.009) / (0.346 - 0.009),
-                    (item.free_sulfur_dioxide as f32 - 2.0) / (289.0 - 2.0),
-                    (item.total_sulfur_dioxide as f32 - 9.0) / (440.0 - 9.0),
-                    (item.density as f32 - 0.98711) / (1.03898 - 0.98711),
-                    (item.ph as f32 - 2.72) / (3.82 - 2.72),
-                    (item.sulphates as f32 - 0.22) / (1.08 - 0.22),
-                    (item.alcohol as f32 - 8.0) / (14.2 - 8.0),
-                ],
+                self.scaler.transform(&item.features()).as_slice(),
                 &self.device,
             );
 
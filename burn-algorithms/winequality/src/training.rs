@@ -1,10 +1,11 @@
 use crate::data::{WineQualityBatcher, WineQualityDataset};
 use crate::model::RegressionModelConfig;
+use burn::data::dataset::Dataset;
 use burn::{
     data::dataloader::DataLoaderBuilder,
     optim::SgdConfig,
     prelude::*,
-    record::{CompactRecorder, NoStdTrainingRecorder},
+    record::{BinBytesRecorder, CompactRecorder, FullPrecisionSettings, NoStdTrainingRecorder},
     tensor::backend::AutodiffBackend,
     train::{
         metric::store::{Aggregate, Direction, Split},
@@ -12,9 +13,7 @@ use burn::{
         LearnerBuilder, MetricEarlyStoppingStrategy, StoppingCondition,
     },
 };
-
-#[cfg(not(feature = "cocos"))]
-use burn::data::dataset::Dataset;
+use lib::{scaler::SCALER_FILE, AlgorithmError, FeatureScaler, ScalerKind};
 
 #[cfg(feature = "cocos")]
 static ARTIFACT_DIR: &str = "results";
@@ -38,28 +37,54 @@ pub struct ExpConfig {
     pub seed: u64,
     #[config(default = 11)]
     pub input_feature_len: usize,
+    /// How the features are normalized, fitted on the train split.
+    #[config(default = "ScalerKind::MinMax")]
+    pub normalization: ScalerKind,
     #[config(default = 5e-3)]
     pub learning_rate: f64,
 }
 
 pub fn run<B: AutodiffBackend>(device: B::Device) {
+    if let Err(err) = train::<B>(device) {
+        lib::exit_with_error(ARTIFACT_DIR, err);
+    }
+}
+
+fn train<B: AutodiffBackend>(device: B::Device) -> Result<(), AlgorithmError> {
+    if cfg!(feature = "cocos") {
+        lib::verify_datasets(ARTIFACT_DIR)?;
+    }
+
     let optimizer = SgdConfig::new();
-    let config = ExpConfig::new(optimizer);
+    let config = lib::load_config(ExpConfig::new(optimizer))?;
     let model = RegressionModelConfig::new(config.input_feature_len).init(&device);
+    let model = if cfg!(feature = "cocos") {
+        lib::datasets::load_pretrained(
+            model,
+            &BinBytesRecorder::<FullPrecisionSettings>::default(),
+            &device,
+        )?
+    } else {
+        model
+    };
     B::seed(config.seed);
 
-    let path = WineQualityDataset::read();
-    let train_dataset = WineQualityDataset::train(&path);
-    let test_dataset = WineQualityDataset::test(&path);
+    let train_dataset = WineQualityDataset::train()?;
+    let test_dataset = WineQualityDataset::test()?;
 
     #[cfg(not(feature = "cocos"))]
     {
         println!("Train Dataset Size: {}", train_dataset.len());
         println!("Test Dataset Size: {}", test_dataset.len());
     }
-    let batcher_train = WineQualityBatcher::<B>::new(device.clone());
+    let scaler = FeatureScaler::fit(
+        config.normalization,
+        train_dataset.iter().map(|item| item.features()),
+    )?;
+
+    let batcher_train = WineQualityBatcher::<B>::new(device.clone(), scaler.clone());
 
-    let batcher_test = WineQualityBatcher::<B::InnerBackend>::new(device.clone());
+    let batcher_test = WineQualityBatcher::<B::InnerBackend>::new(device.clone(), scaler.clone());
 
     let dataloader_train = DataLoaderBuilder::new(batcher_train)
         .batch_size(config.batch_size)
@@ -88,7 +113,7 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
             ))
             .devices(vec![device.clone()])
             .num_epochs(config.num_epochs)
-            .renderer(lib::EmptyMetricsRenderer)
+            .renderer(lib::cocos_metrics_renderer(ARTIFACT_DIR))
             .build(model, config.optimizer.init(), config.learning_rate)
     } else {
         LearnerBuilder::new(ARTIFACT_DIR)
@@ -114,10 +139,20 @@ pub fn run<B: AutodiffBackend>(device: B::Device) {
         .save(format!("{ARTIFACT_DIR}/config.json").as_str())
         .expect("Failed to save config");
 
+    scaler
+        .save(format!("{ARTIFACT_DIR}/{SCALER_FILE}"))
+        .expect("Failed to save feature scaler");
+
     model_trained
         .save_file(
             format!("{ARTIFACT_DIR}/model"),
             &NoStdTrainingRecorder::new(),
         )
         .expect("Failed to save trained model");
+
+    if cfg!(feature = "cocos") {
+        lib::write_results_manifest(ARTIFACT_DIR).expect("Failed to write results manifest");
+    }
+
+    Ok(())
 }